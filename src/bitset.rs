@@ -0,0 +1,83 @@
+/// A fixed-size set of integers in `0..capacity`, stored as one bit per integer.
+///
+/// Combined with `Board::solvable_rank`, it is a much more compact closed list than a `HashSet<Board>`
+/// for boards whose state space is small enough (181,440 states, i.e., about 23 KB, for the 8-puzzle).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BitSet {
+    words: Vec<u64>,
+    capacity: usize,
+    len: usize,
+}
+
+impl BitSet {
+    /// Creates an empty set that can hold the integers in `0..capacity`.
+    pub fn new(capacity: usize) -> BitSet {
+        BitSet {
+            words: vec![0; capacity.div_ceil(64)],
+            capacity,
+            len: 0,
+        }
+    }
+
+    /// The number of integers that the set can hold.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Number of integers in the set.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the set contains `value`.
+    pub fn contains(&self, value: usize) -> bool {
+        assert!(value < self.capacity, "Value out of range");
+        self.words[value / 64] & (1 << (value % 64)) != 0
+    }
+
+    /// Adds `value` to the set, returning `true` if it was not already present.
+    pub fn insert(&mut self, value: usize) -> bool {
+        let absent = !self.contains(value);
+        if absent {
+            self.words[value / 64] |= 1 << (value % 64);
+            self.len += 1;
+        }
+        absent
+    }
+
+    /// Removes `value` from the set, returning `true` if it was present.
+    pub fn remove(&mut self, value: usize) -> bool {
+        let present = self.contains(value);
+        if present {
+            self.words[value / 64] &= !(1 << (value % 64));
+            self.len -= 1;
+        }
+        present
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitset() {
+        let mut set = BitSet::new(130);
+        assert!(set.is_empty());
+        assert!(set.insert(0));
+        assert!(set.insert(129));
+        assert!(set.insert(64));
+        assert!(!set.insert(64));
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(129) && set.contains(64) && !set.contains(63));
+        assert!(set.remove(64));
+        assert!(!set.remove(64));
+        assert!(!set.contains(64));
+        assert_eq!(set.len(), 2);
+    }
+}
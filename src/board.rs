@@ -1,8 +1,5 @@
 use std::fmt::Display;
 
-/// Size of the board. The board is a square of size N x N.
-pub const N: usize = 3;
-
 /// Type of the cell in the board. It is a number between 0 and N^2 - 1.
 pub type Cell = u8;
 
@@ -11,6 +8,9 @@ pub const EMPTY_CELL: Cell = 0;
 
 /// The board is a square of size N x N. It is represented as an array of N arrays of N cells.
 ///
+/// The size is a const generic parameter: `Board<3>` is the 8-puzzle, `Board<4>` the 15-puzzle
+/// and `Board<5>` the 24-puzzle. It is usually inferred from the array passed to `Board::new`.
+///
 /// ```rust
 /// let board = Board::new([[1, 2, 3], [4, 5, 6], [7, 0, 8]]);
 ///
@@ -25,32 +25,102 @@ pub const EMPTY_CELL: Cell = 0;
 /// assert_eq!(new_board.position(2), (0, 1));
 /// ```
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
-pub struct Board {
+pub struct Board<const N: usize> {
     cells: [[Cell; N]; N],
 }
-impl Board {
-    /// The goal state of the 8-puzzle problem.
+
+/// The 8-puzzle.
+pub type Board8 = Board<3>;
+/// The 15-puzzle.
+pub type Board15 = Board<4>;
+/// The 24-puzzle.
+pub type Board24 = Board<5>;
+
+impl<const N: usize> Board<N> {
+    /// The goal state of the puzzle: tiles in increasing order, with the empty cell in the bottom-right corner.
     ///
     /// ```rust
-    /// let goal: Board = Board::GOAL;
+    /// let goal: Board<3> = Board::GOAL;
     /// assert_eq!(goal.value_at(0, 0), 1);
     /// assert_eq!(goal.value_at(2, 2), 0);
     /// ```
-    pub const GOAL: Board = Board::new([[1, 2, 3], [4, 5, 6], [7, 8, 0]]);
+    pub const GOAL: Board<N> = Board::goal();
+
+    pub const fn new(cells: [[Cell; N]; N]) -> Board<N> {
+        Board { cells }
+    }
 
-    pub const fn new(cells: [[Cell; N]; N]) -> Board {
+    /// Builds the goal state, with the tiles `1..N*N` in order followed by the empty cell.
+    const fn goal() -> Board<N> {
+        let mut cells = [[EMPTY_CELL; N]; N];
+        let mut i = 0;
+        while i < N * N - 1 {
+            cells[i / N][i % N] = (i + 1) as Cell;
+            i += 1;
+        }
         Board { cells }
     }
 
+    /// A goal state with the empty cell in the top-left corner, followed by the tiles `1..N*N` in order.
+    ///
+    /// ```rust
+    /// assert_eq!(Board::blank_first_goal(), Board::new([[0, 1, 2], [3, 4, 5], [6, 7, 8]]));
+    /// ```
+    pub const fn blank_first_goal() -> Board<N> {
+        let mut cells = [[EMPTY_CELL; N]; N];
+        let mut i = 1;
+        while i < N * N {
+            cells[i / N][i % N] = i as Cell;
+            i += 1;
+        }
+        Board { cells }
+    }
+
+    /// A goal state where the tiles `1..N*N` are placed in a clockwise spiral from the top-left corner,
+    /// the empty cell ending in the middle of the spiral.
+    ///
+    /// ```rust
+    /// assert_eq!(Board::spiral_goal(), Board::new([[1, 2, 3], [8, 0, 4], [7, 6, 5]]));
+    /// ```
+    pub fn spiral_goal() -> Board<N> {
+        let mut cells = [[EMPTY_CELL; N]; N];
+        let (mut top, mut bottom, mut left, mut right) = (0, N - 1, 0, N - 1);
+        let mut next = 1;
+        let mut place = |x: usize, y: usize| {
+            if next < N * N {
+                cells[x][y] = next as Cell;
+                next += 1;
+            }
+        };
+        while top <= bottom && left <= right {
+            (left..=right).for_each(|y| place(top, y));
+            (top + 1..=bottom).for_each(|x| place(x, right));
+            if top < bottom && left < right {
+                (left..right).rev().for_each(|y| place(bottom, y));
+                (top + 1..bottom).rev().for_each(|x| place(x, left));
+            }
+            if bottom == 0 || right == 0 {
+                break;
+            }
+            (top, bottom, left, right) = (top + 1, bottom - 1, left + 1, right - 1);
+        }
+        Board { cells }
+    }
+
+    /// Number of cells of the board, including the empty one.
+    pub const fn num_cells(&self) -> usize {
+        N * N
+    }
+
     /// Returns the value of the cell at the given position.
-    pub fn value_at(&self, line: usize, column: usize) -> Cell {
+    pub const fn value_at(&self, line: usize, column: usize) -> Cell {
         self.cells[line][column]
     }
 
     /// Returns the result of applying the given action to the board.
     /// If the action is not applicable (the empty cell would move outside the board), returns `None`.
     /// Otherwise, returns the new board (wrapped in `Some(...)`).
-    pub fn apply(&self, direction: Direction) -> Option<Board> {
+    pub fn apply(&self, direction: Direction) -> Option<Board<N>> {
         let (x, y) = self.position(EMPTY_CELL);
         // compute the new coordinates of the empty cell after the move
         let new_coordinates = match direction {
@@ -64,7 +134,7 @@ impl Board {
             Some((new_x, new_y)) => {
                 // empty cell can be moved to the new coordinates
                 // create a new board with the empty cell moved
-                let mut new_cells = self.cells.clone();
+                let mut new_cells = self.cells;
                 new_cells[x][y] = new_cells[new_x][new_y];
                 new_cells[new_x][new_y] = 0;
                 Some(Board::new(new_cells))
@@ -85,19 +155,207 @@ impl Board {
         panic!("No such cell: {value}");
     }
 
+    /// Returns the number of inversions of the board: pairs of tiles that appear in the reverse order
+    /// of their value when reading the board line by line (the empty cell is ignored).
+    pub fn inversions(&self) -> usize {
+        let tiles: Vec<Cell> = self
+            .cells
+            .iter()
+            .flatten()
+            .copied()
+            .filter(|&cell| cell != EMPTY_CELL)
+            .collect();
+        let mut inversions = 0;
+        for i in 0..tiles.len() {
+            for j in (i + 1)..tiles.len() {
+                if tiles[i] > tiles[j] {
+                    inversions += 1;
+                }
+            }
+        }
+        inversions
+    }
+
+    /// Parity of the board that is preserved by every move.
+    ///
+    /// Moving the empty cell horizontally never changes the number of inversions.
+    /// Moving it vertically jumps a tile over `N - 1` others: for odd widths this keeps the parity of the inversions,
+    /// for even widths it flips it, but the line of the empty cell changes as well.
+    fn invariant_parity(&self) -> usize {
+        let (blank_line, _) = self.position(EMPTY_CELL);
+        if N % 2 == 1 {
+            self.inversions() % 2
+        } else {
+            (self.inversions() + blank_line) % 2
+        }
+    }
+
+    /// Returns `true` if the goal state can be reached from this board.
+    ///
+    /// Exactly half of the boards are solvable: those having the same invariant parity as the goal.
+    ///
+    /// ```rust
+    /// assert!(Board::new([[1, 2, 3], [4, 5, 6], [7, 0, 8]]).is_solvable());
+    /// assert!(!Board::new([[2, 1, 3], [4, 5, 6], [7, 8, 0]]).is_solvable());
+    /// ```
+    pub fn is_solvable(&self) -> bool {
+        self.is_solvable_towards(&Board::GOAL)
+    }
+
+    /// Returns `true` if the given goal can be reached from this board.
+    pub fn is_solvable_towards(&self, goal: &Board<N>) -> bool {
+        self.invariant_parity() == goal.invariant_parity()
+    }
+
+    /// Number of distinct boards of width `N`, i.e., `(N²)!` (half of them can reach any given goal).
+    ///
+    /// Panics if it does not fit in a `u128`, i.e., from the 35-puzzle on (see `checked_num_permutations`).
+    pub const fn num_permutations() -> u128 {
+        Self::checked_num_permutations().expect("Too many permutations")
+    }
+
+    /// Number of distinct boards of width `N`, or `None` if it does not fit in a `u128`.
+    pub const fn checked_num_permutations() -> Option<u128> {
+        let mut count: u128 = 1;
+        let mut k = 2;
+        while k <= N * N {
+            count = match count.checked_mul(k as u128) {
+                Some(count) => count,
+                None => return None,
+            };
+            k += 1;
+        }
+        Some(count)
+    }
+
+    /// Only the boards whose permutations can be counted can be ranked (checked when a ranking function is compiled).
+    const RANKABLE: () = assert!(
+        Self::checked_num_permutations().is_some() && N * N <= MAX_RANKED_CELLS,
+        "Boards too large to be ranked"
+    );
+
+    /// The rank of the board in the lexicographic order of the permutations of its cells (read line by line),
+    /// in `0..Board::<N>::num_permutations()`. `Board::from_rank` is its inverse.
+    ///
+    /// ```rust
+    /// assert_eq!(Board::new([[0, 1, 2], [3, 4, 5], [6, 7, 8]]).rank(), 0);
+    /// assert_eq!(Board::new([[0, 1, 2], [3, 4, 5], [6, 8, 7]]).rank(), 1);
+    /// ```
+    pub fn rank(&self) -> u128 {
+        let () = Self::RANKABLE;
+        lehmer_rank(self.cells.as_flattened())
+    }
+
+    /// Builds the board of the given rank (see `Board::rank`).
+    ///
+    /// Panics if the rank is not lower than `Board::<N>::num_permutations()`.
+    pub fn from_rank(rank: u128) -> Board<N> {
+        let () = Self::RANKABLE;
+        assert!(rank < Self::num_permutations(), "Rank out of range");
+        let mut cells = [[EMPTY_CELL; N]; N];
+        lehmer_unrank(rank, cells.as_flattened_mut());
+        Board::new(cells)
+    }
+
+    /// The rank of the board among the boards that can reach the same goals, in `0..Board::<N>::num_permutations() / 2`.
+    /// `Board::from_solvable_rank` is its inverse.
+    ///
+    /// The board is ranked by the position of its empty cell, then by the permutation of its tiles.
+    /// Once the empty cell is placed, the parity of the tiles is fixed, and swapping the last two tiles
+    /// only changes the lowest bit of their rank: dropping this bit gives the bijection.
+    pub fn solvable_rank(&self) -> u128 {
+        let () = Self::RANKABLE;
+        let cells = self.cells.as_flattened();
+        let blank = cells.iter().position(|&c| c == EMPTY_CELL).unwrap() as u128;
+        let mut tiles = [EMPTY_CELL; MAX_RANKED_CELLS];
+        for (tile, &cell) in tiles
+            .iter_mut()
+            .zip(cells.iter().filter(|&&c| c != EMPTY_CELL))
+        {
+            *tile = cell;
+        }
+        blank * (Self::num_permutations() / (N * N) as u128 / 2)
+            + lehmer_rank(&tiles[..N * N - 1]) / 2
+    }
+
+    /// Builds the board of the given solvable rank (see `Board::solvable_rank`) that can reach `goal`.
+    pub fn from_solvable_rank(rank: u128, goal: &Board<N>) -> Board<N> {
+        let () = Self::RANKABLE;
+        let tile_permutations = Self::num_permutations() / (N * N) as u128;
+        assert!(
+            rank < tile_permutations * (N * N) as u128 / 2,
+            "Rank out of range"
+        );
+        let blank = (rank / (tile_permutations / 2)) as usize;
+        let tile_rank = rank % (tile_permutations / 2);
+        let board = |tile_rank| {
+            let mut tiles = [EMPTY_CELL; MAX_RANKED_CELLS];
+            lehmer_unrank(tile_rank, &mut tiles[..N * N - 1]);
+            let mut tiles = tiles.into_iter();
+            let mut cells = [[EMPTY_CELL; N]; N];
+            for (i, cell) in cells.as_flattened_mut().iter_mut().enumerate() {
+                if i != blank {
+                    *cell = tiles.next().unwrap() + 1;
+                }
+            }
+            Board::new(cells)
+        };
+        let even = board(2 * tile_rank);
+        if even.is_solvable_towards(goal) {
+            even
+        } else {
+            board(2 * tile_rank + 1)
+        }
+    }
+
+    /// Returns a compact textual form of the board, that can be parsed back with `str::parse`.
+    ///
+    /// When all tiles have a single digit, the cells are written line by line without separator, the empty cell being `0`.
+    /// Otherwise, the tiles of a line are separated by commas and the lines by slashes.
+    ///
+    /// ```rust
+    /// assert_eq!(Board8::GOAL.to_compact_string(), "123456780");
+    /// assert_eq!(Board15::GOAL.to_compact_string(), "1,2,3,4/5,6,7,8/9,10,11,12/13,14,15,0");
+    /// ```
+    pub fn to_compact_string(self) -> String {
+        if N * N <= 10 {
+            self.cells
+                .iter()
+                .flatten()
+                .map(|cell| cell.to_string())
+                .collect()
+        } else {
+            self.cells
+                .iter()
+                .map(|line| {
+                    line.iter()
+                        .map(|cell| cell.to_string())
+                        .collect::<Vec<_>>()
+                        .join(",")
+                })
+                .collect::<Vec<_>>()
+                .join("/")
+        }
+    }
+
     /// Plays a sequence of moves on the board, printing the board at each step.
     /// Intended for displaying purpose but very slow (the thread will be put to sleep between each frame)
     pub fn play(&self, moves: &[Direction]) {
+        self.play_with_delay(moves, std::time::Duration::from_millis(500));
+    }
+
+    /// Same as `play`, but waits for the given delay between each frame.
+    pub fn play_with_delay(&self, moves: &[Direction], delay: std::time::Duration) {
         // current board from which the play starts
-        let mut current_board = self.clone();
+        let mut current_board = *self;
         println!("{current_board}");
         for &direction in moves {
             if let Some(next) = current_board.apply(direction) {
                 // action is applicable, update the current board
                 current_board = next;
 
-                // wait half a second before displaying
-                std::thread::sleep(std::time::Duration::from_millis(500));
+                // wait before displaying
+                std::thread::sleep(delay);
                 // print the current board
                 println!("{current_board}");
             } else {
@@ -109,52 +367,281 @@ impl Board {
         }
     }
 
-    /// Returs `true` if the given sequence of actions is a valid plan that leads to the goal state.
-    pub fn is_valid_plan(&self, actions: &[Direction]) -> bool {
-        use super::*;
+    /// Plays the given sequence of actions on the board, stopping at the first action that cannot be applied,
+    /// and reports how it went.
+    ///
+    /// ```rust
+    /// let board = Board::new([[1, 2, 3], [4, 5, 6], [0, 7, 8]]);
+    /// let report = board.validate_plan(&[Direction::Right, Direction::Down]);
+    /// assert!(!report.is_valid());
+    /// assert_eq!(report.illegal_move.unwrap().index, 1);
+    /// ```
+    pub fn validate_plan(&self, actions: &[Direction]) -> PlanReport<N> {
+        self.validate_plan_towards(actions, &Board::GOAL)
+    }
+
+    /// Same as `validate_plan`, for a plan that should lead to the given goal.
+    pub fn validate_plan_towards(&self, actions: &[Direction], goal: &Board<N>) -> PlanReport<N> {
         let mut board = *self;
-        let goal =  Board::new([[1, 2, 3], [4, 5, 6], [7, 8, 0]]);
+        let mut illegal_move = None;
 
-        for action in actions {
-            match board.apply(*action) {
-                Some(x) => board = x,
-                None => println!("The action could not be done"),
+        for (index, &direction) in actions.iter().enumerate() {
+            match board.apply(direction) {
+                Some(next) => board = next,
+                None => {
+                    illegal_move = Some(IllegalMove {
+                        index,
+                        direction,
+                        board,
+                    });
+                    break;
+                }
             }
         }
 
-        board == goal
+        PlanReport {
+            plan_length: actions.len(),
+            illegal_move,
+            final_board: board,
+            reaches_goal: board == *goal,
+        }
+    }
+
+    /// Returs `true` if the given sequence of actions is a valid plan that leads to the goal state.
+    pub fn is_valid_plan(&self, actions: &[Direction]) -> bool {
+        self.validate_plan(actions).is_valid()
     }
 }
 
+/// The result of playing a plan on a board, see `Board::validate_plan`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlanReport<const N: usize> {
+    /// Number of actions in the plan.
+    pub plan_length: usize,
+    /// The first action of the plan that could not be applied, if any.
+    pub illegal_move: Option<IllegalMove<N>>,
+    /// The board on which the play ended: after the last action, or where the first illegal action was met.
+    pub final_board: Board<N>,
+    /// Whether the final board is the goal.
+    pub reaches_goal: bool,
+}
+
+impl<const N: usize> PlanReport<N> {
+    /// Returns `true` if every action could be applied and the plan leads to the goal.
+    pub fn is_valid(&self) -> bool {
+        self.illegal_move.is_none() && self.reaches_goal
+    }
+}
+
+/// An action of a plan that could not be applied because it would move the empty cell out of the board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IllegalMove<const N: usize> {
+    /// Index of the action in the plan.
+    pub index: usize,
+    /// Direction of the action.
+    pub direction: Direction,
+    /// The board on which the action could not be applied.
+    pub board: Board<N>,
+}
+
 // Specifies how to display a board in a human-readable way.
 // This is what is used when you use the `{}` format specifier in a `println!` macro.
-impl std::fmt::Display for Board {
+impl<const N: usize> std::fmt::Display for Board<N> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "\n┏━━━┳━━━┳━━━┓\n")?;
+        // each cell is wide enough for the largest tile, plus one space on each side
+        let digits = (N * N - 1).to_string().len();
+        let horizontal = "━".repeat(digits + 2);
+        // writes a horizontal separator line, e.g. `┣━━━╋━━━╋━━━┫`
+        let separator = |f: &mut std::fmt::Formatter<'_>, left: &str, middle: &str, right: &str| {
+            write!(f, "{left}")?;
+            for j in 0..N {
+                write!(f, "{horizontal}")?;
+                write!(f, "{}", if j < N - 1 { middle } else { right })?;
+            }
+            Ok(())
+        };
+
+        writeln!(f)?;
+        separator(f, "┏", "┳", "┓")?;
+        writeln!(f)?;
         for i in 0..N {
             write!(f, "┃")?;
             for j in 0..N {
                 let value_in_cell = self.value_at(i, j);
-                if value_in_cell == 0 {
-                    write!(f, "   ┃")?;
+                if value_in_cell == EMPTY_CELL {
+                    write!(f, " {:digits$} ┃", "")?;
                 } else {
-                    write!(f, " {value_in_cell} ┃")?;
+                    write!(f, " {value_in_cell:>digits$} ┃")?;
                 }
             }
+            writeln!(f)?;
             if i < N - 1 {
-                write!(f, "\n┣━━━╋━━━╋━━━┫\n")?;
+                separator(f, "┣", "╋", "┫")?;
             } else {
-                write!(f, "\n┗━━━┻━━━┻━━━┛\n")?;
+                separator(f, "┗", "┻", "┛")?;
             }
+            writeln!(f)?;
         }
         Ok(())
     }
 }
 
+/// Error returned when parsing a board from a string fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BoardParseError {
+    /// A token is neither a number nor a blank marker.
+    InvalidTile(String),
+    /// A tile is too large for the size of the board.
+    OutOfRange { tile: usize, max: usize },
+    /// A tile appears more than once.
+    Duplicate(Cell),
+    /// No cell is empty.
+    MissingBlank,
+    /// The number of cells does not match the size of the board.
+    WrongTileCount { expected: usize, found: usize },
+}
+
+impl Display for BoardParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BoardParseError::InvalidTile(token) => write!(f, "invalid tile `{token}`"),
+            BoardParseError::OutOfRange { tile, max } => {
+                write!(f, "tile {tile} is out of range (largest tile is {max})")
+            }
+            BoardParseError::Duplicate(tile) => write!(f, "tile {tile} appears several times"),
+            BoardParseError::MissingBlank => write!(f, "the board has no empty cell"),
+            BoardParseError::WrongTileCount { expected, found } => {
+                write!(f, "expected {expected} cells but found {found}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BoardParseError {}
+
+/// Size of the scratch buffers of the ranking functions: enough for the 24-puzzle, the largest board that can be ranked
+/// since `36!` does not fit in a `u128`.
+const MAX_RANKED_CELLS: usize = 32;
+
+/// Lexicographic rank of a sequence of distinct values among their permutations, i.e., its Lehmer code
+/// read as a number in the factorial base.
+fn lehmer_rank(values: &[Cell]) -> u128 {
+    let mut rank = 0;
+    for (i, &value) in values.iter().enumerate() {
+        // the digit of position i is the number of later values smaller than the current one
+        let smaller = values[i + 1..].iter().filter(|&&v| v < value).count();
+        rank = rank * (values.len() - i) as u128 + smaller as u128;
+    }
+    rank
+}
+
+/// Fills `values` with the permutation of `0..values.len()` of the given lexicographic rank, inverse of `lehmer_rank`.
+fn lehmer_unrank(mut rank: u128, values: &mut [Cell]) {
+    let len = values.len();
+    // digits of the Lehmer code, computed from the least significant one (last value)
+    for (i, value) in values.iter_mut().enumerate().rev() {
+        let base = (len - i) as u128;
+        *value = (rank % base) as Cell;
+        rank /= base;
+    }
+    // each digit is the index of the value among the values not used yet (one bit each)
+    assert!(len <= MAX_RANKED_CELLS, "Too many values to unrank");
+    let mut remaining: u64 = (1 << len) - 1;
+    for value in values.iter_mut() {
+        let mut bits = remaining;
+        for _ in 0..*value {
+            bits &= bits - 1;
+        }
+        *value = bits.trailing_zeros() as Cell;
+        remaining &= !(1 << *value);
+    }
+}
+
+/// Splits the textual form of a board into the tokens of its cells (see the `FromStr` implementation of `Board`).
+fn cell_tokens(s: &str) -> Vec<&str> {
+    let is_separator = |c: char| c.is_whitespace() || matches!(c, ',' | ';' | '/');
+    let s = s.trim_matches(is_separator);
+    if s.contains(is_separator) {
+        s.split(is_separator).filter(|t| !t.is_empty()).collect()
+    } else {
+        // compact form, one character per cell
+        s.char_indices()
+            .map(|(i, c)| &s[i..i + c.len_utf8()])
+            .collect()
+    }
+}
+
+/// Returns the width of the board written in the given string, guessed from its number of cells.
+/// Returns `None` if the number of cells is not a square.
+///
+/// ```rust
+/// assert_eq!(board_width("123456780"), Some(3));
+/// assert_eq!(board_width("1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 _"), Some(4));
+/// ```
+pub fn board_width(s: &str) -> Option<usize> {
+    let cells = cell_tokens(s).len();
+    (1..=cells).find(|width| width * width == cells)
+}
+
+/// Parses a board from its cells, given line by line.
+///
+/// The cells are either separated by whitespace, commas, semicolons or slashes (`"1 2 3, 4 5 6, 7 8 _"`)
+/// or, when all tiles have a single digit, written next to each other (`"123456780"`).
+/// The empty cell is written `0`, `_` or `.`.
+///
+/// ```rust
+/// let board: Board<3> = "1 2 3 / 4 5 6 / 7 8 _".parse().unwrap();
+/// assert_eq!(board, "12345678.".parse().unwrap());
+/// ```
+impl<const N: usize> std::str::FromStr for Board<N> {
+    type Err = BoardParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens = cell_tokens(s);
+        let mut values = Vec::with_capacity(tokens.len());
+        for token in tokens {
+            let value = match token {
+                "_" | "." => EMPTY_CELL as usize,
+                _ => token
+                    .parse::<usize>()
+                    .map_err(|_| BoardParseError::InvalidTile(token.to_string()))?,
+            };
+            if value >= N * N {
+                return Err(BoardParseError::OutOfRange {
+                    tile: value,
+                    max: N * N - 1,
+                });
+            }
+            let value = value as Cell;
+            if values.contains(&value) {
+                return Err(BoardParseError::Duplicate(value));
+            }
+            values.push(value);
+        }
+        // the count is checked first, so that an empty input is not reported as a missing blank
+        if values.len() != N * N {
+            // all the tiles are there, only the empty cell was left out
+            if values.len() == N * N - 1 && !values.contains(&EMPTY_CELL) {
+                return Err(BoardParseError::MissingBlank);
+            }
+            return Err(BoardParseError::WrongTileCount {
+                expected: N * N,
+                found: values.len(),
+            });
+        }
+
+        let mut cells = [[EMPTY_CELL; N]; N];
+        for (i, value) in values.into_iter().enumerate() {
+            cells[i / N][i % N] = value;
+        }
+        Ok(Board::new(cells))
+    }
+}
+
 /// The possible directions to move the empty cell.
 ///
 /// A direction is *one of* `Up`, `Down`, `Left` or `Right`.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum Direction {
     Up,
     Down,
@@ -175,6 +662,22 @@ impl Direction {
             Direction::Right => Direction::Left,
         }
     }
+
+    /// Returns the position next to `(line, column)` in this direction, e.g., where the empty cell goes when it moves.
+    ///
+    /// Panics if the position would be outside of the board on the top or left side.
+    ///
+    /// ```rust
+    /// assert_eq!(Direction::Up.step((2, 1)), (1, 1));
+    /// ```
+    pub fn step(&self, (line, column): (usize, usize)) -> (usize, usize) {
+        match self {
+            Direction::Up => (line - 1, column),
+            Direction::Down => (line + 1, column),
+            Direction::Left => (line, column - 1),
+            Direction::Right => (line, column + 1),
+        }
+    }
 }
 
 // Implements prettry printing for the `Direction` enum.
@@ -208,8 +711,103 @@ pub const DIRECTIONS: [Direction; 4] = [
     Direction::Right,
 ];
 
+/// What the directions of a written plan refer to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MoveConvention {
+    /// Directions in which the empty cell moves, as in `Direction`.
+    Blank,
+    /// Directions in which the tiles slide into the empty cell, i.e. the opposite of the movement of the empty cell.
+    Tile,
+}
+
+/// Error returned when a plan cannot be parsed, pointing at the offending character.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PlanParseError {
+    /// Position of the offending character in the input, counted in characters from 0.
+    pub position: usize,
+    /// The offending character.
+    pub character: char,
+}
+
+impl Display for PlanParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid move `{}` at position {}",
+            self.character, self.position
+        )
+    }
+}
+
+impl std::error::Error for PlanParseError {}
+
+/// Parses a plan written as a sequence of moves.
+///
+/// Each move is either a letter (`U`, `D`, `L`, `R`), an arrow (`↑`, `↓`, `←`, `→`, as displayed by `Direction`)
+/// or a word (`up`, `down`, `left`, `right`), in any case. Moves may be separated by whitespace, commas or semicolons.
+/// The directions are interpreted according to the given convention.
+///
+/// ```rust
+/// use Direction::*;
+/// assert_eq!(parse_plan("RUL", MoveConvention::Blank), Ok(vec![Right, Up, Left]));
+/// assert_eq!(parse_plan("right, up ←", MoveConvention::Blank), Ok(vec![Right, Up, Left]));
+/// assert_eq!(parse_plan("RUL", MoveConvention::Tile), Ok(vec![Left, Down, Right]));
+/// ```
+pub fn parse_plan(s: &str, convention: MoveConvention) -> Result<Vec<Direction>, PlanParseError> {
+    let chars: Vec<char> = s.chars().collect();
+    let letter = |c: char| match c.to_ascii_uppercase() {
+        'U' | '↑' => Some(Direction::Up),
+        'D' | '↓' => Some(Direction::Down),
+        'L' | '←' => Some(Direction::Left),
+        'R' | '→' => Some(Direction::Right),
+        _ => None,
+    };
+
+    let mut plan = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if chars[i].is_whitespace() || matches!(chars[i], ',' | ';') {
+            i += 1;
+            continue;
+        }
+        // a run of letters is either a word or a sequence of single-letter moves
+        let end = (i..chars.len())
+            .find(|&j| !chars[j].is_ascii_alphabetic())
+            .unwrap_or(chars.len())
+            .max(i + 1);
+        let run: String = chars[i..end].iter().collect::<String>().to_lowercase();
+        let word = match run.as_str() {
+            "up" => Some(Direction::Up),
+            "down" => Some(Direction::Down),
+            "left" => Some(Direction::Left),
+            "right" => Some(Direction::Right),
+            _ => None,
+        };
+        match word {
+            Some(direction) => plan.push(direction),
+            None => {
+                for (position, &character) in chars.iter().enumerate().take(end).skip(i) {
+                    let direction = letter(character).ok_or(PlanParseError {
+                        position,
+                        character,
+                    })?;
+                    plan.push(direction);
+                }
+            }
+        }
+        i = end;
+    }
+
+    if convention == MoveConvention::Tile {
+        for direction in plan.iter_mut() {
+            *direction = direction.opposite();
+        }
+    }
+    Ok(plan)
+}
+
 /// A list of instances of the 8-puzzle problem, with know distance to the goal state.
-pub const INSTANCES: [(u32, Board); 32] = [
+pub const INSTANCES: [(u32, Board<3>); 32] = [
     (0, Board::new([[1, 2, 3], [4, 5, 6], [7, 8, 0]])), // goal state (reachable in 0 actions)
     (1, Board::new([[1, 2, 3], [4, 5, 6], [7, 0, 8]])), // state reachable in 1 action)
     (2, Board::new([[1, 2, 3], [4, 5, 6], [0, 7, 8]])), // state reachable in 2 actions)
@@ -299,4 +897,335 @@ mod tests {
         // invalid plan (moves the empty cell out of the board)
         assert!(!board.is_valid_plan(&[Left]));
     }
+
+    #[test]
+    fn test_solvable() {
+        for (_, board) in &INSTANCES {
+            assert!(board.is_solvable());
+        }
+        assert_eq!(
+            Board::new([[2, 1, 3], [4, 5, 6], [7, 8, 0]]).inversions(),
+            1
+        );
+        assert!(!Board::new([[2, 1, 3], [4, 5, 6], [7, 8, 0]]).is_solvable());
+        assert!(!Board::new([[1, 2, 3], [4, 5, 6], [8, 7, 0]]).is_solvable());
+
+        // on even widths, the line of the empty cell matters
+        let board = Board::new([[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 0], [13, 14, 15, 12]]);
+        assert!(board.is_solvable());
+        let board = Board::new([[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12], [13, 15, 14, 0]]);
+        assert!(!board.is_solvable());
+        // same permutation of the tiles, but the empty cell is one line up
+        let board = Board::new([[1, 2, 3, 4], [5, 6, 7, 8], [0, 9, 10, 11], [12, 13, 14, 15]]);
+        assert_eq!(board.inversions(), 0);
+        assert!(!board.is_solvable());
+        let board = Board::new([[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12], [0, 13, 14, 15]]);
+        assert!(board.is_solvable());
+    }
+
+    #[test]
+    fn test_rank() {
+        assert_eq!(Board8::num_permutations(), 362_880);
+        assert_eq!(
+            Board24::num_permutations(),
+            15_511_210_043_330_985_984_000_000
+        );
+        assert_eq!(
+            Board24::checked_num_permutations(),
+            Some(Board24::num_permutations())
+        );
+        assert_eq!(Board::<6>::checked_num_permutations(), None);
+        assert_eq!(Board8::GOAL.rank(), 46_233);
+        assert_eq!(
+            Board::new([[8, 7, 6], [5, 4, 3], [2, 1, 0]]).rank(),
+            362_879
+        );
+
+        for rank in (0..Board8::num_permutations()).step_by(7) {
+            assert_eq!(Board8::from_rank(rank).rank(), rank);
+        }
+        // the solvable ranks are a bijection between the solvable boards and 0..9!/2
+        for rank in (0..Board8::num_permutations() / 2).step_by(3) {
+            let board = Board8::from_solvable_rank(rank, &Board::GOAL);
+            assert!(board.is_solvable());
+            assert_eq!(board.solvable_rank(), rank);
+        }
+
+        for board in [Board15::GOAL, Board15::spiral_goal()] {
+            assert_eq!(Board::from_rank(board.rank()), board);
+            assert_eq!(
+                Board::from_solvable_rank(board.solvable_rank(), &Board::GOAL),
+                board
+            );
+        }
+        let board = Board24::blank_first_goal();
+        assert_eq!(board.rank(), 0);
+        assert_eq!(
+            Board24::from_rank(Board24::num_permutations() - 1).value_at(0, 0),
+            24
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let board = Board::new([[1, 2, 3], [4, 5, 6], [7, 0, 8]]);
+        assert_eq!("123456708".parse(), Ok(board));
+        assert_eq!("1234567_8".parse(), Ok(board));
+        assert_eq!("  1234567.8\n".parse(), Ok(board));
+        assert_eq!("1 2 3\n4 5 6\n7 _ 8".parse(), Ok(board));
+        assert_eq!("1,2,3,4,5,6,7,0,8".parse(), Ok(board));
+        assert!("[1, 2, 3]; [4, 5, 6]; [7, 0, 8]"
+            .parse::<Board<3>>()
+            .is_err());
+
+        let board: Board<4> = "1 2 3 4 / 5 6 7 8 / 9 10 11 12 / 13 14 . 15"
+            .parse()
+            .unwrap();
+        assert_eq!(board.apply(Direction::Right), Some(Board::GOAL));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        use BoardParseError::*;
+        assert_eq!(
+            "12345670".parse::<Board<3>>(),
+            Err(WrongTileCount {
+                expected: 9,
+                found: 8
+            })
+        );
+        assert_eq!(
+            "1234567890".parse::<Board<3>>(),
+            Err(OutOfRange { tile: 9, max: 8 })
+        );
+        assert_eq!("123456781".parse::<Board<3>>(), Err(Duplicate(1)));
+        assert_eq!("1_345678_".parse::<Board<3>>(), Err(Duplicate(EMPTY_CELL)));
+        assert_eq!("12345678".parse::<Board<3>>(), Err(MissingBlank));
+        for empty in ["", "   ", " , ; "] {
+            assert_eq!(
+                empty.parse::<Board<3>>(),
+                Err(WrongTileCount {
+                    expected: 9,
+                    found: 0
+                })
+            );
+        }
+        assert_eq!(
+            "1 2 3".parse::<Board<3>>(),
+            Err(WrongTileCount {
+                expected: 9,
+                found: 3
+            })
+        );
+        assert_eq!(
+            "12345x780".parse::<Board<3>>(),
+            Err(InvalidTile("x".to_string()))
+        );
+        assert_eq!(
+            "1 2 3 4 5 6 7 8 -1".parse::<Board<3>>(),
+            Err(InvalidTile("-1".to_string()))
+        );
+        assert_eq!(
+            "123456780".parse::<Board<4>>(),
+            Err(WrongTileCount {
+                expected: 16,
+                found: 9
+            })
+        );
+        assert_eq!(
+            MissingBlank.to_string(),
+            "the board has no empty cell".to_string()
+        );
+    }
+
+    #[test]
+    fn test_board_width() {
+        assert_eq!(board_width("123456780"), Some(3));
+        assert_eq!(board_width("1,2,3/4,5,6/7,8,_"), Some(3));
+        assert_eq!(board_width(&Board24::GOAL.to_compact_string()), Some(5));
+        assert_eq!(board_width("12345678"), None);
+        assert_eq!(board_width(""), None);
+    }
+
+    #[test]
+    fn test_compact_round_trip() {
+        for (_, board) in &INSTANCES {
+            assert_eq!(board.to_compact_string().parse(), Ok(*board));
+        }
+        assert_eq!(
+            Board::new([[1, 2, 3], [4, 5, 6], [7, 0, 8]]).to_compact_string(),
+            "123456708"
+        );
+        let board = Board::new([[12, 1, 2, 3], [4, 5, 6, 7], [8, 9, 10, 11], [0, 13, 14, 15]]);
+        assert_eq!(
+            board.to_compact_string(),
+            "12,1,2,3/4,5,6,7/8,9,10,11/0,13,14,15"
+        );
+        assert_eq!(board.to_compact_string().parse(), Ok(board));
+        assert_eq!(Board24::GOAL.to_compact_string().parse(), Ok(Board24::GOAL));
+    }
+
+    #[test]
+    fn test_parse_plan() {
+        use Direction::*;
+        use MoveConvention::*;
+        assert_eq!(parse_plan("", Blank), Ok(vec![]));
+        assert_eq!(parse_plan("UDLR", Blank), Ok(vec![Up, Down, Left, Right]));
+        assert_eq!(parse_plan("udlr", Blank), Ok(vec![Up, Down, Left, Right]));
+        assert_eq!(parse_plan("↑↓←→", Blank), Ok(vec![Up, Down, Left, Right]));
+        assert_eq!(
+            parse_plan("Up down, LEFT;right", Blank),
+            Ok(vec![Up, Down, Left, Right])
+        );
+        assert_eq!(
+            parse_plan(" R U → left D ", Blank),
+            Ok(vec![Right, Up, Right, Left, Down])
+        );
+        assert_eq!(parse_plan("UDLR", Tile), Ok(vec![Down, Up, Right, Left]));
+
+        // the display of a plan can be parsed back
+        let plan = [Right, Up, Right, Down];
+        let written: String = plan.iter().map(|d| d.to_string()).collect();
+        assert_eq!(parse_plan(&written, Blank), Ok(plan.to_vec()));
+
+        let board = Board::new([[1, 2, 3], [4, 5, 6], [0, 7, 8]]);
+        assert!(board.is_valid_plan(&parse_plan("RR", Blank).unwrap()));
+        assert!(board.is_valid_plan(&parse_plan("left left", Tile).unwrap()));
+    }
+
+    #[test]
+    fn test_parse_plan_errors() {
+        use MoveConvention::*;
+        assert_eq!(
+            parse_plan("UDXR", Blank),
+            Err(PlanParseError {
+                position: 2,
+                character: 'X'
+            })
+        );
+        assert_eq!(
+            parse_plan("↑ ↓ ⇐", Blank),
+            Err(PlanParseError {
+                position: 4,
+                character: '⇐'
+            })
+        );
+        // not a word, and `p` is not a move
+        assert_eq!(
+            parse_plan("up upp", Blank),
+            Err(PlanParseError {
+                position: 4,
+                character: 'p'
+            })
+        );
+        assert_eq!(
+            parse_plan("R2", Tile).unwrap_err().to_string(),
+            "invalid move `2` at position 1"
+        );
+    }
+
+    #[test]
+    fn test_validate_plan() {
+        use Direction::*;
+        let board = Board::new([[1, 2, 3], [4, 5, 6], [0, 7, 8]]);
+
+        let report = board.validate_plan(&[Right, Right]);
+        assert!(report.is_valid());
+        assert_eq!(report.plan_length, 2);
+        assert_eq!(report.illegal_move, None);
+        assert_eq!(report.final_board, Board::GOAL);
+        assert!(report.reaches_goal);
+
+        // all actions are applicable but the goal is not reached
+        let report = board.validate_plan(&[Right, Up]);
+        assert!(!report.is_valid());
+        assert_eq!(report.illegal_move, None);
+        assert_eq!(
+            report.final_board,
+            Board::new([[1, 2, 3], [4, 0, 6], [7, 5, 8]])
+        );
+        assert!(!report.reaches_goal);
+
+        // the play stops at the first illegal action, even if the following ones would lead to the goal
+        let report = board.validate_plan(&[Right, Down, Left, Right, Right]);
+        assert!(!report.is_valid());
+        assert_eq!(report.plan_length, 5);
+        let illegal = report.illegal_move.unwrap();
+        assert_eq!(illegal.index, 1);
+        assert_eq!(illegal.direction, Down);
+        assert_eq!(illegal.board, Board::new([[1, 2, 3], [4, 5, 6], [7, 0, 8]]));
+        assert_eq!(report.final_board, illegal.board);
+        assert!(!report.reaches_goal);
+
+        let report = Board8::GOAL.validate_plan(&[]);
+        assert!(report.is_valid());
+        assert_eq!(report.plan_length, 0);
+    }
+
+    #[test]
+    fn test_other_goals() {
+        use Direction::*;
+        assert_eq!(
+            Board::blank_first_goal(),
+            Board::new([[0, 1, 2, 3], [4, 5, 6, 7], [8, 9, 10, 11], [12, 13, 14, 15]])
+        );
+        assert_eq!(
+            Board::spiral_goal(),
+            Board::new([[1, 2, 3, 4], [12, 13, 14, 5], [11, 0, 15, 6], [10, 9, 8, 7]])
+        );
+        assert_eq!(
+            Board::spiral_goal(),
+            Board::new([
+                [1, 2, 3, 4, 5],
+                [16, 17, 18, 19, 6],
+                [15, 24, 0, 20, 7],
+                [14, 23, 22, 21, 8],
+                [13, 12, 11, 10, 9]
+            ])
+        );
+
+        // on the 8-puzzle, the spiral goal is not reachable from the usual one
+        let spiral: Board<3> = Board::spiral_goal();
+        assert!(!spiral.is_solvable());
+        assert!(Board8::GOAL.is_solvable_towards(&Board::blank_first_goal()));
+
+        let board = Board::new([[1, 0, 2], [3, 4, 5], [6, 7, 8]]);
+        let goal = Board::blank_first_goal();
+        assert!(board.validate_plan_towards(&[Left], &goal).is_valid());
+        assert!(!board.validate_plan(&[Left]).is_valid());
+    }
+
+    #[test]
+    fn test_goal() {
+        assert_eq!(Board8::GOAL, Board::new([[1, 2, 3], [4, 5, 6], [7, 8, 0]]));
+        assert_eq!(
+            Board15::GOAL,
+            Board::new([[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12], [13, 14, 15, 0]])
+        );
+        assert_eq!(Board24::GOAL.value_at(4, 3), 24);
+        assert_eq!(Board24::GOAL.position(EMPTY_CELL), (4, 4));
+    }
+
+    #[test]
+    fn test_larger_boards() {
+        use Direction::*;
+        let board = Board::new([[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12], [13, 14, 0, 15]]);
+        assert_eq!(board.num_cells(), 16);
+        assert_eq!(board.position(EMPTY_CELL), (3, 2));
+        assert_eq!(board.apply(Down), None);
+        assert_eq!(board.apply(Right), Some(Board15::GOAL));
+        assert!(board.is_valid_plan(&[Left, Right, Right]));
+    }
+
+    #[test]
+    fn test_display() {
+        let board = Board::new([[1, 2, 3], [4, 5, 6], [7, 0, 8]]);
+        assert_eq!(
+            board.to_string(),
+            "\n┏━━━┳━━━┳━━━┓\n┃ 1 ┃ 2 ┃ 3 ┃\n┣━━━╋━━━╋━━━┫\n┃ 4 ┃ 5 ┃ 6 ┃\n┣━━━╋━━━╋━━━┫\n┃ 7 ┃   ┃ 8 ┃\n┗━━━┻━━━┻━━━┛\n"
+        );
+        let rendered = Board15::GOAL.to_string();
+        assert!(rendered.starts_with("\n┏━━━━┳━━━━┳━━━━┳━━━━┓\n┃  1 ┃  2 ┃  3 ┃  4 ┃\n"));
+        assert!(rendered.contains("┃ 13 ┃ 14 ┃ 15 ┃    ┃\n┗━━━━┻━━━━┻━━━━┻━━━━┛\n"));
+    }
 }
@@ -0,0 +1,914 @@
+use crate::board::*;
+use crate::distance_table::*;
+use crate::generator::*;
+use crate::heuristics::*;
+use crate::pattern_database::*;
+use crate::search::*;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Exit code when the command succeeded: a plan was found, or the given plan is valid.
+pub const EXIT_SUCCESS: i32 = 0;
+/// Exit code when the board is unsolvable, or the given plan does not lead to the goal.
+pub const EXIT_FAILURE: i32 = 1;
+/// Exit code when the command line or the board or moves given on it are invalid.
+pub const EXIT_INVALID_INPUT: i32 = 2;
+/// Exit code when the search stopped before finding a plan (limit reached or cancelled).
+pub const EXIT_STOPPED: i32 = 3;
+
+const USAGE: &str = "\
+Usage: puzzle <command> [arguments]
+
+Commands:
+  solve <board> [--algo astar|ida|bfs|dijkstra|iddfs|focal|greedy|bidirectional] [--heuristic blind|hamming|manhattan|linear-conflict|pdb|perfect]
+        [--pdb <file>] [--table <file>] [--goal <goal>] [--weight <w>] [<limits>]
+      Finds a plan leading from the board to the goal. With a weight 1 < w <= 1000, A* (weighted) and focal search
+      find plans costing at most w times the optimal cost, faster. Greedy search finds plans even faster,
+      but without any bound on their cost.
+  validate <board> <moves> [--convention blank|tile] [--goal <goal>]
+      Checks that the moves lead from the board to the goal.
+  play <board> <moves> [--delay-ms <ms>] [--convention blank|tile] [--goal <goal>]
+      Displays the moves played on the board.
+  generate [--size 3|4|5] [--count <n>] [--depth <d>] [--attempts <n>] [--seed <seed>]
+      Prints random solvable boards, at exactly `d` moves from the goal if a depth is given
+      (trying at most `attempts` random walks per board).
+  bench [--algo <algo>] [--heuristic <name>] [--weight <w>] [<limits>]
+      Solves all the known 8-puzzle instances and reports the statistics of each search, then the total number
+      of expansions and cost of the plans of each algorithm (compared to the optimal cost).
+      All algorithms but iddfs are run by default. The limits apply to each instance.
+  table [--output <file>] [--goal <goal>]
+      Computes the distance to the goal of every 8-puzzle board and prints the number of boards at each distance
+      and the hardest boards. The table can be saved for the perfect heuristic (`--heuristic perfect --table <file>`).
+
+The limits stop a search before it finds a plan: [--timeout-ms <ms>] [--max-expanded <n>] [--max-stored <n>]
+[--max-memory-mb <mb>] (the memory used by the stored states being roughly estimated).
+
+Boards are given line by line, e.g. `123456780` or `\"1 2 3 4 / 5 6 7 8 / 9 10 11 12 / 13 14 15 _\"`.
+The goal is a board, or one of `standard` (empty cell last, the default), `blank-first` or `spiral`.
+Moves are sequences of U, D, L, R, arrows or words (up, down, left, right), e.g. `RUL` or `\"right up left\"`.
+They give the direction of the empty cell, or of the tile moved with `--convention tile`.
+
+Exit codes: 0 on success, 1 if the board is unsolvable or the plan invalid, 2 on invalid input,
+3 if the search stopped before finding a plan.";
+
+/// Runs the command given by the command-line arguments (without the program name) and returns the exit code.
+pub fn run(args: &[String]) -> i32 {
+    let Some((command, args)) = args.split_first() else {
+        eprintln!("{USAGE}");
+        return EXIT_INVALID_INPUT;
+    };
+    let result = match command.as_str() {
+        "solve" => solve(args),
+        "validate" => validate(args),
+        "play" => play(args),
+        "generate" => generate(args),
+        "bench" => bench(args),
+        "table" => table(args),
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            Ok(EXIT_SUCCESS)
+        }
+        _ => Err(format!("unknown command `{command}`")),
+    };
+    match result {
+        Ok(code) => code,
+        Err(message) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            EXIT_INVALID_INPUT
+        }
+    }
+}
+
+/// Arguments of a command: positional arguments and options given as `--name value` or `--name=value`.
+struct Args {
+    positional: Vec<String>,
+    options: HashMap<String, String>,
+}
+
+impl Args {
+    /// Separates positional arguments and options, only accepting the options with the given names.
+    fn parse(args: &[String], accepted: &[&str]) -> Result<Args, String> {
+        let mut positional = Vec::new();
+        let mut options = HashMap::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let Some(option) = arg.strip_prefix("--") else {
+                positional.push(arg.clone());
+                continue;
+            };
+            let (name, value) = match option.split_once('=') {
+                Some((name, value)) => (name, value.to_string()),
+                None => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| format!("missing value for option `--{option}`"))?;
+                    (option, value.clone())
+                }
+            };
+            if !accepted.contains(&name) {
+                return Err(format!("unknown option `--{name}`"));
+            }
+            options.insert(name.to_string(), value);
+        }
+        Ok(Args {
+            positional,
+            options,
+        })
+    }
+
+    /// Returns the positional arguments, checking that there are exactly `K` of them.
+    fn positional<const K: usize>(&self) -> Result<[&str; K], String> {
+        let values: Vec<&str> = self.positional.iter().map(|s| s.as_str()).collect();
+        values
+            .try_into()
+            .map_err(|_| format!("expected {K} arguments, got {}", self.positional.len()))
+    }
+
+    /// Returns the value of an option, or `default` if it was not given.
+    fn option<T: FromStr>(&self, name: &str, default: T) -> Result<T, String> {
+        match self.options.get(name) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| format!("invalid value `{value}` for option `--{name}`")),
+            None => Ok(default),
+        }
+    }
+
+    /// Returns the value of an option, or `None` if it was not given.
+    fn optional<T: FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        self.options
+            .get(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| format!("invalid value `{value}` for option `--{name}`"))
+            })
+            .transpose()
+    }
+}
+
+/// A board of any of the supported sizes.
+enum AnyBoard {
+    Puzzle8(Board<3>),
+    Puzzle15(Board<4>),
+    Puzzle24(Board<5>),
+}
+
+fn parse_board(s: &str) -> Result<AnyBoard, String> {
+    let parsed = match board_width(s) {
+        Some(4) => s.parse().map(AnyBoard::Puzzle15),
+        Some(5) => s.parse().map(AnyBoard::Puzzle24),
+        // let the parser of the 8-puzzle report the error for other sizes
+        _ => s.parse().map(AnyBoard::Puzzle8),
+    };
+    parsed.map_err(|e| format!("invalid board `{s}`: {e}"))
+}
+
+/// Parses moves, pointing at the offending character in the error message.
+fn parse_moves(s: &str, convention: MoveConvention) -> Result<Vec<Direction>, String> {
+    parse_plan(s, convention).map_err(|e| format!("{e}\n  {s}\n  {}^", " ".repeat(e.position)))
+}
+
+/// Reads the `--convention` option telling what the written moves refer to.
+fn move_convention(args: &Args) -> Result<MoveConvention, String> {
+    match args.option("convention", "blank".to_string())?.as_str() {
+        "blank" => Ok(MoveConvention::Blank),
+        "tile" => Ok(MoveConvention::Tile),
+        convention => Err(format!("unknown move convention `{convention}`")),
+    }
+}
+
+/// Writes a plan as a sequence of arrows.
+fn format_plan(plan: &[Direction]) -> String {
+    plan.iter().map(|d| d.to_string()).collect()
+}
+
+/// The search algorithms that can be selected from the command line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Algorithm {
+    AStar,
+    Ida,
+    Bfs,
+    Focal,
+    Greedy,
+    Bidirectional,
+    Dijkstra,
+    Iddfs,
+}
+
+/// The algorithms run by `bench` by default (plain iterative deepening takes ages on the hardest instances).
+const ALGORITHMS: [Algorithm; 7] = [
+    Algorithm::AStar,
+    Algorithm::Ida,
+    Algorithm::Bfs,
+    Algorithm::Dijkstra,
+    Algorithm::Focal,
+    Algorithm::Greedy,
+    Algorithm::Bidirectional,
+];
+
+impl FromStr for Algorithm {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "astar" => Ok(Algorithm::AStar),
+            "ida" => Ok(Algorithm::Ida),
+            "bfs" => Ok(Algorithm::Bfs),
+            "focal" => Ok(Algorithm::Focal),
+            "greedy" => Ok(Algorithm::Greedy),
+            "bidirectional" => Ok(Algorithm::Bidirectional),
+            "dijkstra" => Ok(Algorithm::Dijkstra),
+            "iddfs" => Ok(Algorithm::Iddfs),
+            _ => Err(()),
+        }
+    }
+}
+
+impl std::fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Algorithm::AStar => "astar",
+            Algorithm::Ida => "ida",
+            Algorithm::Bfs => "bfs",
+            Algorithm::Focal => "focal",
+            Algorithm::Greedy => "greedy",
+            Algorithm::Bidirectional => "bidirectional",
+            Algorithm::Dijkstra => "dijkstra",
+            Algorithm::Iddfs => "iddfs",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl Algorithm {
+    /// Runs the algorithm within the given limits, the weight being only used by weighted A* and focal search,
+    /// and the heuristic being ignored by the uninformed searches (breadth-first, Dijkstra and iterative deepening).
+    fn run<const N: usize>(
+        self,
+        board: Board<N>,
+        goal: &Board<N>,
+        heuristic: &Heuristic,
+        weight: f64,
+        limits: &SearchLimits,
+    ) -> (SearchOutcome, Stats) {
+        match self {
+            Algorithm::AStar => weighted_search_with_limits(board, goal, heuristic, weight, limits),
+            Algorithm::Focal => focal_search_with_limits(board, goal, heuristic, weight, limits),
+            Algorithm::Greedy => greedy_search_with_limits(board, goal, heuristic, limits),
+            Algorithm::Bidirectional => {
+                bidirectional_search_with_limits(board, goal, heuristic, limits)
+            }
+            Algorithm::Ida => ida_star_with_limits(board, goal, heuristic, limits),
+            Algorithm::Bfs => breadth_first_search_with_limits(board, goal, limits),
+            Algorithm::Dijkstra => dijkstra_with_limits(board, goal, limits),
+            Algorithm::Iddfs => iterative_deepening_search_with_limits(board, goal, true, limits),
+        }
+    }
+
+    /// Largest ratio between the cost of the plans found and the optimal cost, if the algorithm guarantees one.
+    fn cost_bound(self, weight: f64) -> Option<f64> {
+        match self {
+            Algorithm::AStar | Algorithm::Focal => Some(weight),
+            Algorithm::Ida
+            | Algorithm::Bfs
+            | Algorithm::Dijkstra
+            | Algorithm::Bidirectional
+            | Algorithm::Iddfs => Some(1.0),
+            Algorithm::Greedy => None,
+        }
+    }
+}
+
+/// The tile groups of the pattern database used by default for each board width.
+fn default_pattern_groups(width: usize) -> Vec<Vec<Cell>> {
+    match width {
+        3 => vec![vec![1, 2, 3, 4], vec![5, 6, 7, 8]],
+        4 => vec![
+            vec![1, 2, 3, 4, 7],
+            vec![5, 6, 9, 10, 13],
+            vec![8, 11, 12, 14, 15],
+        ],
+        _ => (1..(width * width) as Cell)
+            .collect::<Vec<_>>()
+            .chunks(4)
+            .map(|group| group.to_vec())
+            .collect(),
+    }
+}
+
+/// Reads the `--goal` option: either a board or one of the names `standard`, `blank-first` and `spiral`.
+fn goal_option<const N: usize>(args: &Args) -> Result<Board<N>, String> {
+    match args.options.get("goal").map(|goal| goal.as_str()) {
+        None | Some("standard") => Ok(Board::GOAL),
+        Some("blank-first") => Ok(Board::blank_first_goal()),
+        Some("spiral") => Ok(Board::spiral_goal()),
+        Some(goal) => goal
+            .parse()
+            .map_err(|e| format!("invalid goal `{goal}`: {e}")),
+    }
+}
+
+/// The weight given by the `--weight` option (1 by default), that must be between 1 and `MAX_WEIGHT`.
+fn weight_option(args: &Args) -> Result<f64, String> {
+    let weight: f64 = args.option("weight", 1.0)?;
+    if !(1.0..=MAX_WEIGHT).contains(&weight) {
+        return Err(format!(
+            "invalid weight {weight}, it must be between 1 and {MAX_WEIGHT}"
+        ));
+    }
+    Ok(weight)
+}
+
+/// The search limits given by the `--timeout-ms`, `--max-expanded`, `--max-stored` and `--max-memory-mb` options,
+/// none by default. The timeout starts now, so the limits must be read right before each search.
+fn limits_option(args: &Args) -> Result<SearchLimits, String> {
+    let limits = SearchLimits {
+        max_expanded: args.optional("max-expanded")?,
+        max_stored: args.optional("max-stored")?,
+        max_bytes: args
+            .optional::<usize>("max-memory-mb")?
+            .map(|mb| mb.saturating_mul(1 << 20)),
+        ..SearchLimits::default()
+    };
+    Ok(match args.optional("timeout-ms")? {
+        Some(ms) => limits.with_timeout(Duration::from_millis(ms)),
+        None => limits,
+    })
+}
+
+/// The options of the search limits, accepted by `solve` and `bench`.
+const LIMIT_OPTIONS: [&str; 4] = ["timeout-ms", "max-expanded", "max-stored", "max-memory-mb"];
+
+/// Builds the heuristic given by the `--heuristic` option (Manhattan by default) for boards of width `N`.
+/// The pattern database is loaded from the `--pdb` file if given, built with the default groups otherwise,
+/// and the distance table of the perfect heuristic is loaded from the `--table` file or built.
+fn heuristic<const N: usize>(args: &Args, goal: &Board<N>) -> Result<Heuristic, String> {
+    match args.option("heuristic", "manhattan".to_string())?.as_str() {
+        "blind" => Ok(Heuristic::Blind),
+        "hamming" => Ok(Heuristic::Hamming),
+        "manhattan" => Ok(Heuristic::Manhattan),
+        "linear-conflict" => Ok(Heuristic::LinearConflict),
+        "pdb" => {
+            let pdb = match args.options.get("pdb") {
+                Some(path) => PatternDatabase::load(path)
+                    .map_err(|e| format!("cannot load pattern database `{path}`: {e}"))?,
+                None => PatternDatabase::build_towards(&default_pattern_groups(N), goal),
+            };
+            if pdb.width() != N {
+                return Err(format!(
+                    "the pattern database is for boards of width {}",
+                    pdb.width()
+                ));
+            }
+            if !pdb.has_goal(goal) {
+                return Err("the pattern database is for another goal".to_string());
+            }
+            Ok(Heuristic::PatternDatabase(Arc::new(pdb)))
+        }
+        "perfect" => {
+            if N != 3 {
+                return Err("the perfect heuristic is only available for the 8-puzzle".to_string());
+            }
+            let table = match args.options.get("table") {
+                Some(path) => DistanceTable::load(path)
+                    .map_err(|e| format!("cannot load distance table `{path}`: {e}"))?,
+                None => DistanceTable::build_towards(goal),
+            };
+            if !table.has_goal(goal) {
+                return Err("the distance table is for another goal".to_string());
+            }
+            Ok(Heuristic::DistanceTable(Arc::new(table)))
+        }
+        name => Err(format!("unknown heuristic `{name}`")),
+    }
+}
+
+fn solve(args: &[String]) -> Result<i32, String> {
+    let options = ["algo", "heuristic", "pdb", "table", "goal", "weight"];
+    let args = Args::parse(args, &[&options[..], &LIMIT_OPTIONS].concat())?;
+    let [board] = args.positional()?;
+    match parse_board(board)? {
+        AnyBoard::Puzzle8(board) => solve_board(board, &args),
+        AnyBoard::Puzzle15(board) => solve_board(board, &args),
+        AnyBoard::Puzzle24(board) => solve_board(board, &args),
+    }
+}
+
+fn solve_board<const N: usize>(board: Board<N>, args: &Args) -> Result<i32, String> {
+    let algorithm = args.option("algo", Algorithm::AStar)?;
+    let goal = goal_option(args)?;
+    let heuristic = heuristic(args, &goal)?;
+    let weight = weight_option(args)?;
+
+    let (outcome, stats) = algorithm.run(board, &goal, &heuristic, weight, &limits_option(args)?);
+    let code = match outcome {
+        SearchOutcome::Solved(plan) => {
+            println!("{} {}", plan.len(), format_plan(&plan));
+            EXIT_SUCCESS
+        }
+        SearchOutcome::Unsolvable => {
+            println!("unsolvable");
+            EXIT_FAILURE
+        }
+        SearchOutcome::ExhaustedLimit { limit } => {
+            println!("stopped: {limit:?} limit reached");
+            EXIT_STOPPED
+        }
+        SearchOutcome::Cancelled => {
+            println!("cancelled");
+            EXIT_STOPPED
+        }
+    };
+    eprintln!("{stats}");
+    Ok(code)
+}
+
+fn validate(args: &[String]) -> Result<i32, String> {
+    let args = Args::parse(args, &["convention", "goal"])?;
+    let [board, moves] = args.positional()?;
+    let moves = parse_moves(moves, move_convention(&args)?)?;
+    match parse_board(board)? {
+        AnyBoard::Puzzle8(board) => validate_board(board, &moves, &args),
+        AnyBoard::Puzzle15(board) => validate_board(board, &moves, &args),
+        AnyBoard::Puzzle24(board) => validate_board(board, &moves, &args),
+    }
+}
+
+fn validate_board<const N: usize>(
+    board: Board<N>,
+    moves: &[Direction],
+    args: &Args,
+) -> Result<i32, String> {
+    let goal = goal_option(args)?;
+    Ok(report_plan(&board.validate_plan_towards(moves, &goal)))
+}
+
+/// Prints the outcome of the validation of a plan and returns the corresponding exit code.
+fn report_plan<const N: usize>(report: &PlanReport<N>) -> i32 {
+    if report.is_valid() {
+        println!("valid plan of {} moves", report.plan_length);
+        return EXIT_SUCCESS;
+    }
+    match report.illegal_move {
+        Some(illegal) => println!(
+            "invalid: move {} ({}) cannot be played on {}",
+            illegal.index + 1,
+            illegal.direction,
+            illegal.board.to_compact_string()
+        ),
+        None => println!(
+            "invalid: the plan of {} moves ends on {} instead of the goal",
+            report.plan_length,
+            report.final_board.to_compact_string()
+        ),
+    }
+    EXIT_FAILURE
+}
+
+fn play(args: &[String]) -> Result<i32, String> {
+    let args = Args::parse(args, &["delay-ms", "convention", "goal"])?;
+    let [board, moves] = args.positional()?;
+    let moves = parse_moves(moves, move_convention(&args)?)?;
+    let delay = Duration::from_millis(args.option("delay-ms", 500)?);
+    match parse_board(board)? {
+        AnyBoard::Puzzle8(board) => play_board(board, &moves, delay, &args),
+        AnyBoard::Puzzle15(board) => play_board(board, &moves, delay, &args),
+        AnyBoard::Puzzle24(board) => play_board(board, &moves, delay, &args),
+    }
+}
+
+fn play_board<const N: usize>(
+    board: Board<N>,
+    moves: &[Direction],
+    delay: Duration,
+    args: &Args,
+) -> Result<i32, String> {
+    // check that all moves can be played before starting the animation
+    let report = board.validate_plan_towards(moves, &goal_option(args)?);
+    if let Some(illegal) = report.illegal_move {
+        return Err(format!(
+            "move {} ({}) cannot be played",
+            illegal.index + 1,
+            illegal.direction
+        ));
+    }
+    board.play_with_delay(moves, delay);
+    Ok(if report.reaches_goal {
+        EXIT_SUCCESS
+    } else {
+        EXIT_FAILURE
+    })
+}
+
+fn generate(args: &[String]) -> Result<i32, String> {
+    let args = Args::parse(args, &["size", "count", "depth", "attempts", "seed"])?;
+    args.positional::<0>()?;
+    let default_seed = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64);
+    let mut generator = Generator::new(args.option("seed", default_seed)?);
+    let count: usize = args.option("count", 1)?;
+    let depth: Option<u32> = args
+        .options
+        .get("depth")
+        .map(|_| args.option("depth", 0))
+        .transpose()?;
+    let attempts = args.option("attempts", 10_000)?;
+    match args.option("size", 3)? {
+        3 => generate_boards::<3>(&mut generator, count, depth, attempts),
+        4 => generate_boards::<4>(&mut generator, count, depth, attempts),
+        5 => generate_boards::<5>(&mut generator, count, depth, attempts),
+        size => Err(format!("unsupported board size {size}")),
+    }
+}
+
+fn generate_boards<const N: usize>(
+    generator: &mut Generator,
+    count: usize,
+    depth: Option<u32>,
+    attempts: usize,
+) -> Result<i32, String> {
+    for _ in 0..count {
+        let board: Board<N> = match depth {
+            Some(depth) => match generator.at_distance(depth, attempts) {
+                Some(board) => board,
+                None => {
+                    eprintln!("no board found at distance {depth}");
+                    return Ok(EXIT_FAILURE);
+                }
+            },
+            None => generator.random_solvable(),
+        };
+        println!("{}", board.to_compact_string());
+    }
+    Ok(EXIT_SUCCESS)
+}
+
+fn bench(args: &[String]) -> Result<i32, String> {
+    let options = ["algo", "heuristic", "pdb", "table", "weight"];
+    let args = Args::parse(args, &[&options[..], &LIMIT_OPTIONS].concat())?;
+    args.positional::<0>()?;
+    let algorithms = match args.options.get("algo") {
+        Some(_) => vec![args.option("algo", Algorithm::AStar)?],
+        None => ALGORITHMS.to_vec(),
+    };
+    let weight = weight_option(&args)?;
+    let heuristic = heuristic(&args, &Board8::GOAL)?;
+
+    let mut code = EXIT_SUCCESS;
+    for algorithm in algorithms {
+        let mut total_expanded = 0;
+        let mut total_cost = 0;
+        let mut total_runtime = Duration::ZERO;
+        let mut stopped = 0;
+        for (cost, board) in &INSTANCES {
+            // each instance gets the whole timeout
+            let limits = limits_option(&args)?;
+            let (outcome, stats) = algorithm.run(*board, &Board::GOAL, &heuristic, weight, &limits);
+            println!(
+                "{algorithm:<13} {:>2} {} {stats}",
+                cost,
+                board.to_compact_string()
+            );
+            // the plans of weighted A* and focal search may be longer, up to `weight` times the optimal cost,
+            // and those of greedy search are not bounded
+            let within_bound = |plan: &[Direction]| {
+                plan.len() as u32 >= *cost
+                    && algorithm
+                        .cost_bound(weight)
+                        .is_none_or(|bound| plan.len() as f64 <= bound * *cost as f64)
+            };
+            if let SearchOutcome::ExhaustedLimit { limit } = outcome {
+                // not a failure, but the totals are incomplete
+                println!("{algorithm:<13} stopped: {limit:?} limit reached");
+                stopped += 1;
+                if code == EXIT_SUCCESS {
+                    code = EXIT_STOPPED;
+                }
+            } else if !outcome.plan().is_some_and(within_bound) {
+                eprintln!("{algorithm}: wrong plan for {}", board.to_compact_string());
+                code = EXIT_FAILURE;
+            }
+            total_expanded += stats.expanded;
+            total_cost += stats.solution_cost.unwrap_or(0);
+            total_runtime += stats.runtime;
+        }
+        // the total cost of the plans, compared to the total optimal cost, tells the quality of the plans
+        let optimal_cost: u32 = INSTANCES.iter().map(|(cost, _)| cost).sum();
+        println!(
+            "{algorithm:<13} total expanded={total_expanded} cost={total_cost}/{optimal_cost} stopped={stopped} runtime={total_runtime:?}"
+        );
+    }
+    Ok(code)
+}
+
+fn table(args: &[String]) -> Result<i32, String> {
+    let args = Args::parse(args, &["output", "goal"])?;
+    args.positional::<0>()?;
+    let goal: Board8 = goal_option(&args)?;
+    let table = DistanceTable::build_towards(&goal);
+    for (distance, count) in table.histogram().iter().enumerate() {
+        println!("{distance:>2} {count}");
+    }
+    println!(
+        "states={} max_distance={}",
+        table.num_states(),
+        table.max_distance()
+    );
+    for board in table.hardest::<3>() {
+        println!("hardest {}", board.to_compact_string());
+    }
+
+    let mut code = EXIT_SUCCESS;
+    if goal == Board::GOAL {
+        for (cost, board) in &INSTANCES {
+            if table.distance(board) != Some(*cost) {
+                eprintln!("wrong expected cost for {}", board.to_compact_string());
+                code = EXIT_FAILURE;
+            }
+        }
+    }
+    if let Some(path) = args.options.get("output") {
+        table
+            .save(path)
+            .map_err(|e| format!("cannot write distance table `{path}`: {e}"))?;
+    }
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_with(args: &[&str]) -> i32 {
+        let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+        run(&args)
+    }
+
+    #[test]
+    fn test_solve() {
+        assert_eq!(run_with(&["solve", "123456708"]), EXIT_SUCCESS);
+        assert_eq!(
+            run_with(&["solve", "123456708", "--algo", "ida"]),
+            EXIT_SUCCESS
+        );
+        assert_eq!(
+            run_with(&["solve", "123456708", "--algo=bfs"]),
+            EXIT_SUCCESS
+        );
+        assert_eq!(
+            run_with(&["solve", "867254301", "--algo", "focal", "--weight", "1.5"]),
+            EXIT_SUCCESS
+        );
+        assert_eq!(
+            run_with(&["solve", "867254301", "--weight", "2"]),
+            EXIT_SUCCESS
+        );
+        assert_eq!(
+            run_with(&["solve", "867254301", "--algo", "bidirectional"]),
+            EXIT_SUCCESS
+        );
+        assert_eq!(
+            run_with(&["solve", "123485076", "--algo", "dijkstra"]),
+            EXIT_SUCCESS
+        );
+        assert_eq!(
+            run_with(&["solve", "123485076", "--algo", "iddfs"]),
+            EXIT_SUCCESS
+        );
+        assert_eq!(
+            run_with(&["solve", "123456708", "--weight", "0.5"]),
+            EXIT_INVALID_INPUT
+        );
+        for algorithm in ["astar", "focal"] {
+            assert_eq!(
+                run_with(&["solve", "867254301", "--algo", algorithm, "--weight", "1e7"]),
+                EXIT_INVALID_INPUT
+            );
+            assert_eq!(
+                run_with(&[
+                    "solve",
+                    "867254301",
+                    "--algo",
+                    algorithm,
+                    "--weight",
+                    "1000"
+                ]),
+                EXIT_SUCCESS
+            );
+        }
+        assert_eq!(
+            run_with(&["solve", "123456708", "--heuristic", "linear-conflict"]),
+            EXIT_SUCCESS
+        );
+        assert_eq!(
+            run_with(&["solve", "123456708", "--heuristic", "pdb"]),
+            EXIT_SUCCESS
+        );
+        assert_eq!(
+            run_with(&["solve", "1 2 3 4 / 5 6 7 8 / 9 10 11 12 / 13 14 _ 15"]),
+            EXIT_SUCCESS
+        );
+        assert_eq!(run_with(&["solve", "213456780"]), EXIT_FAILURE);
+        assert_eq!(
+            run_with(&[
+                "solve",
+                "102345678",
+                "--goal",
+                "blank-first",
+                "--heuristic",
+                "pdb"
+            ]),
+            EXIT_SUCCESS
+        );
+        assert_eq!(
+            run_with(&["solve", "123804765", "--goal", "123804765"]),
+            EXIT_SUCCESS
+        );
+        assert_eq!(
+            run_with(&["solve", "123456780", "--goal", "spiral"]),
+            EXIT_FAILURE
+        );
+        assert_eq!(
+            run_with(&["solve", "867254301", "--max-expanded", "100"]),
+            EXIT_STOPPED
+        );
+        assert_eq!(
+            run_with(&["solve", "867254301", "--algo", "ida", "--timeout-ms", "0"]),
+            EXIT_STOPPED
+        );
+        assert_eq!(
+            run_with(&["solve", "123456708", "--max-memory-mb", "1"]),
+            EXIT_SUCCESS
+        );
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert_eq!(run_with(&[]), EXIT_INVALID_INPUT);
+        assert_eq!(run_with(&["unknown"]), EXIT_INVALID_INPUT);
+        assert_eq!(run_with(&["solve"]), EXIT_INVALID_INPUT);
+        assert_eq!(run_with(&["solve", "12345678"]), EXIT_INVALID_INPUT);
+        assert_eq!(run_with(&["solve", "123456788"]), EXIT_INVALID_INPUT);
+        assert_eq!(
+            run_with(&["solve", "123456708", "--algo", "dfs"]),
+            EXIT_INVALID_INPUT
+        );
+        assert_eq!(
+            run_with(&["solve", "123456708", "--heuristic"]),
+            EXIT_INVALID_INPUT
+        );
+        assert_eq!(
+            run_with(&["solve", "123456708", "--colour", "red"]),
+            EXIT_INVALID_INPUT
+        );
+        assert_eq!(
+            run_with(&["validate", "123456708", "RX"]),
+            EXIT_INVALID_INPUT
+        );
+        assert_eq!(run_with(&["generate", "--size", "7"]), EXIT_INVALID_INPUT);
+        assert_eq!(
+            run_with(&["solve", "123456708", "--goal", "12345678"]),
+            EXIT_INVALID_INPUT
+        );
+        assert_eq!(
+            run_with(&["solve", "123456708", "--max-stored", "-1"]),
+            EXIT_INVALID_INPUT
+        );
+    }
+
+    #[test]
+    fn test_validate_and_play() {
+        assert_eq!(run_with(&["validate", "123456078", "RR"]), EXIT_SUCCESS);
+        assert_eq!(run_with(&["validate", "123456078", "→→"]), EXIT_SUCCESS);
+        assert_eq!(
+            run_with(&["validate", "123456078", "right right"]),
+            EXIT_SUCCESS
+        );
+        assert_eq!(
+            run_with(&["validate", "123456078", "LL", "--convention", "tile"]),
+            EXIT_SUCCESS
+        );
+        assert_eq!(
+            run_with(&["validate", "123456078", "RR", "--convention", "tile"]),
+            EXIT_FAILURE
+        );
+        assert_eq!(run_with(&["validate", "123456078", "RL"]), EXIT_FAILURE);
+        assert_eq!(run_with(&["validate", "123456078", "D"]), EXIT_FAILURE);
+        assert_eq!(
+            run_with(&["validate", "102345678", "L", "--goal", "blank-first"]),
+            EXIT_SUCCESS
+        );
+        assert_eq!(
+            run_with(&["play", "123456078", "rr", "--delay-ms", "0"]),
+            EXIT_SUCCESS
+        );
+        assert_eq!(
+            run_with(&["play", "123456078", "R", "--delay-ms", "0"]),
+            EXIT_FAILURE
+        );
+        assert_eq!(
+            run_with(&["play", "123456078", "L", "--delay-ms", "0"]),
+            EXIT_INVALID_INPUT
+        );
+    }
+
+    #[test]
+    fn test_generate() {
+        assert_eq!(
+            run_with(&["generate", "--count", "3", "--seed", "1"]),
+            EXIT_SUCCESS
+        );
+        assert_eq!(
+            run_with(&["generate", "--size", "4", "--depth", "10", "--seed", "1"]),
+            EXIT_SUCCESS
+        );
+        assert_eq!(
+            run_with(&["generate", "--depth", "35", "--attempts", "3"]),
+            EXIT_FAILURE
+        );
+    }
+
+    #[test]
+    fn test_bench() {
+        assert_eq!(
+            run_with(&["bench", "--algo", "ida", "--heuristic", "linear-conflict"]),
+            EXIT_SUCCESS
+        );
+        assert_eq!(
+            run_with(&["bench", "--algo", "focal", "--weight", "1.2"]),
+            EXIT_SUCCESS
+        );
+        assert_eq!(
+            run_with(&[
+                "bench",
+                "--algo",
+                "greedy",
+                "--heuristic",
+                "linear-conflict"
+            ]),
+            EXIT_SUCCESS
+        );
+        // the hardest instances stop, the others are solved
+        assert_eq!(
+            run_with(&["bench", "--algo", "bfs", "--max-stored", "1000"]),
+            EXIT_STOPPED
+        );
+    }
+
+    #[test]
+    fn test_table() {
+        let path =
+            std::env::temp_dir().join(format!("puzzle-cli-table-{}.bin", std::process::id()));
+        let path = path.to_str().unwrap();
+        assert_eq!(run_with(&["table", "--output", path]), EXIT_SUCCESS);
+        assert_eq!(
+            run_with(&[
+                "solve",
+                "867254301",
+                "--heuristic",
+                "perfect",
+                "--table",
+                path
+            ]),
+            EXIT_SUCCESS
+        );
+        // the table does not match the goal
+        assert_eq!(
+            run_with(&[
+                "solve",
+                "123456708",
+                "--heuristic=perfect",
+                "--table",
+                path,
+                "--goal",
+                "blank-first"
+            ]),
+            EXIT_INVALID_INPUT
+        );
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(
+            run_with(&[
+                "solve",
+                "123456708",
+                "--heuristic",
+                "perfect",
+                "--table",
+                path
+            ]),
+            EXIT_INVALID_INPUT
+        );
+        assert_eq!(
+            run_with(&[
+                "solve",
+                "1 2 3 4 / 5 6 7 8 / 9 10 11 12 / 13 14 _ 15",
+                "--heuristic",
+                "perfect"
+            ]),
+            EXIT_INVALID_INPUT
+        );
+    }
+}
@@ -0,0 +1,357 @@
+use crate::board::*;
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// The exact distance to the goal of every board that can reach it, obtained by enumerating the whole state space.
+///
+/// Distances are indexed by `Board::solvable_rank`, one byte per board: 181,440 bytes for the 8-puzzle.
+/// Larger puzzles have far too many states to be enumerated.
+///
+/// ```rust
+/// let table = DistanceTable::build::<3>();
+/// assert_eq!(table.max_distance(), 31);
+/// let board = Board::new([[8, 7, 3], [2, 0, 5], [1, 4, 6]]);
+/// let cost = table.distance(&board);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct DistanceTable {
+    /// Width of the boards of the table.
+    width: usize,
+    /// Cells of the goal board, line by line.
+    goal: Vec<Cell>,
+    /// Distance to the goal of each board, indexed by its solvable rank.
+    distances: Vec<u8>,
+}
+
+/// Marks a board whose distance is not known yet when building the table.
+const UNKNOWN: u8 = u8::MAX;
+
+/// Largest width of the boards whose state space can be enumerated.
+const MAX_WIDTH: usize = 3;
+
+/// Magic bytes at the start of a saved distance table.
+const MAGIC: &[u8; 4] = b"DST1";
+
+impl DistanceTable {
+    /// Enumerates the boards that can reach `Board::GOAL`, see `build_towards`.
+    pub fn build<const N: usize>() -> DistanceTable {
+        DistanceTable::build_towards(&Board::<N>::GOAL)
+    }
+
+    /// Enumerates all the boards that can reach the goal with a breadth-first search backward from it,
+    /// recording the depth at which each board is first reached (moves are reversible, so it is also its distance to the goal).
+    ///
+    /// Panics if the board is larger than `3 x 3`.
+    pub fn build_towards<const N: usize>(goal: &Board<N>) -> DistanceTable {
+        assert!(
+            N <= MAX_WIDTH,
+            "Boards larger than 3x3 cannot be enumerated"
+        );
+        let mut distances = vec![UNKNOWN; num_states(N)];
+        distances[goal.solvable_rank() as usize] = 0;
+        let mut layer = vec![*goal];
+        let mut depth = 0;
+        while !layer.is_empty() {
+            depth += 1;
+            let mut next = Vec::new();
+            for board in layer {
+                for direction in DIRECTIONS {
+                    if let Some(successor) = board.apply(direction) {
+                        let rank = successor.solvable_rank() as usize;
+                        if distances[rank] == UNKNOWN {
+                            distances[rank] = depth;
+                            next.push(successor);
+                        }
+                    }
+                }
+            }
+            layer = next;
+        }
+        DistanceTable {
+            width: N,
+            goal: cells(goal),
+            distances,
+        }
+    }
+
+    /// Width of the boards of the table.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns `true` if the table was built towards the given goal.
+    pub fn has_goal<const N: usize>(&self, goal: &Board<N>) -> bool {
+        N == self.width && self.goal == cells(goal)
+    }
+
+    /// Number of boards in the table, i.e., that can reach the goal.
+    pub fn num_states(&self) -> usize {
+        self.distances.len()
+    }
+
+    /// The exact number of moves needed to reach the goal of the table from the board,
+    /// or `None` if the goal cannot be reached.
+    ///
+    /// Panics if the board does not have the width of the table.
+    pub fn distance<const N: usize>(&self, board: &Board<N>) -> Option<u32> {
+        assert_eq!(
+            N, self.width,
+            "Distance table built for boards of width {}",
+            self.width
+        );
+        let goal = self.goal::<N>();
+        if !board.is_solvable_towards(&goal) {
+            return None;
+        }
+        Some(self.distances[board.solvable_rank() as usize] as u32)
+    }
+
+    /// The exact cost of reaching the goal, used as a (perfect) heuristic.
+    ///
+    /// Panics if the board does not have the width, or the goal is not the one, the table was built for,
+    /// or if the board cannot reach the goal.
+    pub fn estimate_towards<const N: usize>(&self, board: &Board<N>, goal: &Board<N>) -> u32 {
+        assert!(self.has_goal(goal), "Distance table built for another goal");
+        self.distance(board)
+            .expect("The board cannot reach the goal")
+    }
+
+    /// Largest distance to the goal, over all boards.
+    pub fn max_distance(&self) -> u32 {
+        self.distances.iter().copied().max().unwrap_or(0) as u32
+    }
+
+    /// Number of boards at each distance from the goal, from 0 to `max_distance`.
+    pub fn histogram(&self) -> Vec<usize> {
+        let mut histogram = vec![0; self.max_distance() as usize + 1];
+        for &distance in &self.distances {
+            histogram[distance as usize] += 1;
+        }
+        histogram
+    }
+
+    /// All the boards of the table with their distance to the goal, in increasing order of rank.
+    pub fn boards<const N: usize>(&self) -> impl Iterator<Item = (Board<N>, u32)> + '_ {
+        let goal = self.goal::<N>();
+        self.distances
+            .iter()
+            .enumerate()
+            .map(move |(rank, &distance)| {
+                (
+                    Board::from_solvable_rank(rank as u128, &goal),
+                    distance as u32,
+                )
+            })
+    }
+
+    /// The boards at the given distance from the goal, in increasing order of rank.
+    pub fn boards_at<const N: usize>(&self, distance: u32) -> Vec<Board<N>> {
+        let goal = self.goal::<N>();
+        (0..self.distances.len())
+            .filter(|&rank| self.distances[rank] as u32 == distance)
+            .map(|rank| Board::from_solvable_rank(rank as u128, &goal))
+            .collect()
+    }
+
+    /// The boards that are the farthest from the goal.
+    pub fn hardest<const N: usize>(&self) -> Vec<Board<N>> {
+        self.boards_at(self.max_distance())
+    }
+
+    /// The goal of the table, as a board of width `N`.
+    fn goal<const N: usize>(&self) -> Board<N> {
+        assert_eq!(N, self.width);
+        let mut cells = [[EMPTY_CELL; N]; N];
+        cells.as_flattened_mut().copy_from_slice(&self.goal);
+        Board::new(cells)
+    }
+
+    /// Saves the table to a binary file.
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        self.write_to(&mut file)?;
+        file.flush()
+    }
+
+    /// Loads a table previously written with `save`.
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<DistanceTable> {
+        let mut file = std::io::BufReader::new(std::fs::File::open(path)?);
+        DistanceTable::read_from(&mut file)
+    }
+
+    /// Writes the table in binary form: the magic bytes `DST1`, the width, the cells of the goal,
+    /// then the distances (whose number is prefixed as a little-endian `u64`).
+    pub fn write_to(&self, out: &mut impl Write) -> std::io::Result<()> {
+        out.write_all(MAGIC)?;
+        out.write_all(&[self.width as u8])?;
+        out.write_all(&self.goal)?;
+        out.write_all(&(self.distances.len() as u64).to_le_bytes())?;
+        out.write_all(&self.distances)
+    }
+
+    /// Reads a table written with `write_to`.
+    pub fn read_from(input: &mut impl Read) -> std::io::Result<DistanceTable> {
+        let invalid = |message: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, message);
+
+        let mut magic = [0u8; 4];
+        input.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a distance table file"));
+        }
+        let mut width = [0u8; 1];
+        input.read_exact(&mut width)?;
+        let width = width[0] as usize;
+        if width == 0 || width > MAX_WIDTH {
+            return Err(invalid("unsupported board width"));
+        }
+        let mut goal = vec![0; width * width];
+        input.read_exact(&mut goal)?;
+        let mut sorted = goal.clone();
+        sorted.sort();
+        if sorted
+            .iter()
+            .enumerate()
+            .any(|(i, &cell)| cell as usize != i)
+        {
+            return Err(invalid("invalid goal"));
+        }
+
+        let mut len = [0u8; 8];
+        input.read_exact(&mut len)?;
+        if u64::from_le_bytes(len) as usize != num_states(width) {
+            return Err(invalid("table size does not match the width"));
+        }
+        let mut distances = vec![0; num_states(width)];
+        input.read_exact(&mut distances)?;
+        if distances.contains(&UNKNOWN) {
+            return Err(invalid("missing distance"));
+        }
+        Ok(DistanceTable {
+            width,
+            goal,
+            distances,
+        })
+    }
+}
+
+// Only print the shape of the table, not its (large) content.
+impl std::fmt::Debug for DistanceTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DistanceTable")
+            .field("width", &self.width)
+            .field("goal", &self.goal)
+            .field("num_states", &self.num_states())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Cells of the board, line by line.
+fn cells<const N: usize>(board: &Board<N>) -> Vec<Cell> {
+    (0..N * N).map(|i| board.value_at(i / N, i % N)).collect()
+}
+
+/// Number of boards of the given width that can reach a given goal: `(width²)! / 2`.
+fn num_states(width: usize) -> usize {
+    (1..=width * width).product::<usize>() / 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::heuristics::*;
+    use crate::search::*;
+    use std::sync::{Arc, OnceLock};
+
+    /// The table of the 8-puzzle, built once for all the tests.
+    fn table() -> &'static DistanceTable {
+        static TABLE: OnceLock<DistanceTable> = OnceLock::new();
+        TABLE.get_or_init(DistanceTable::build::<3>)
+    }
+
+    #[test]
+    fn test_enumeration() {
+        let table = table();
+        assert_eq!(table.num_states(), 181_440);
+        assert_eq!(table.max_distance(), 31);
+        let histogram = table.histogram();
+        assert_eq!(histogram.iter().sum::<usize>(), 181_440);
+        assert_eq!(&histogram[..4], &[1, 2, 4, 8]);
+        assert_eq!(histogram[31], 2);
+
+        let hardest: Vec<Board8> = table.hardest();
+        assert_eq!(hardest.len(), 2);
+        assert!(hardest.contains(&Board::new([[8, 6, 7], [2, 5, 4], [3, 0, 1]])));
+        assert!(hardest.contains(&Board::new([[6, 4, 7], [8, 5, 0], [3, 2, 1]])));
+
+        // the expected costs of the known instances are exact
+        for (cost, board) in &INSTANCES {
+            assert_eq!(table.distance(board), Some(*cost));
+        }
+        assert_eq!(table.distance(&Board8::GOAL), Some(0));
+        assert_eq!(
+            table.distance(&Board::new([[2, 1, 3], [4, 5, 6], [7, 8, 0]])),
+            None
+        );
+    }
+
+    #[test]
+    fn test_heuristics_admissible() {
+        let table = table();
+        let heuristics = [
+            Heuristic::Hamming,
+            Heuristic::Manhattan,
+            Heuristic::LinearConflict,
+        ];
+        for board in table.boards_at::<3>(20).into_iter().step_by(50) {
+            let distance = table.distance(&board).unwrap();
+            for heuristic in &heuristics {
+                assert!(heuristic.estimate(&board) <= distance);
+            }
+        }
+    }
+
+    #[test]
+    fn test_perfect_heuristic() {
+        let heuristic = Heuristic::DistanceTable(Arc::new(table().clone()));
+        for (cost, board) in &INSTANCES {
+            assert_eq!(heuristic.estimate(board), *cost);
+            // with a perfect heuristic, A* only expands states on optimal paths
+            let (outcome, stats) = search(*board, &Board::GOAL, &heuristic);
+            assert_eq!(outcome.plan().map(|p| p.len()), Some(*cost as usize));
+            assert!(stats.layers.iter().all(|layer| layer.f == *cost));
+        }
+    }
+
+    #[test]
+    fn test_small_board() {
+        // the 3-puzzle has 12 states on a single cycle
+        let table = DistanceTable::build::<2>();
+        assert_eq!(table.histogram(), vec![1, 2, 2, 2, 2, 2, 1]);
+        let goal = Board::new([[0, 1], [2, 3]]);
+        let table = DistanceTable::build_towards(&goal);
+        assert!(table.has_goal(&goal));
+        assert!(!table.has_goal(&Board::<2>::GOAL));
+        assert_eq!(table.distance(&goal), Some(0));
+    }
+
+    #[test]
+    fn test_save_load() {
+        let table = DistanceTable::build::<2>();
+        let mut bytes = Vec::new();
+        table.write_to(&mut bytes).unwrap();
+        assert_eq!(
+            DistanceTable::read_from(&mut bytes.as_slice()).unwrap(),
+            table
+        );
+
+        let path = std::env::temp_dir().join(format!("puzzle-table-{}.bin", std::process::id()));
+        table.save(&path).unwrap();
+        let loaded = DistanceTable::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, table);
+
+        // corrupted files are rejected
+        assert!(DistanceTable::read_from(&mut &bytes[1..]).is_err());
+        assert!(DistanceTable::read_from(&mut &bytes[..bytes.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,194 @@
+use crate::board::*;
+use crate::heuristics::*;
+use crate::search::*;
+
+/// A seeded generator of random instances of the puzzle.
+///
+/// Two generators created with the same seed produce the same sequence of boards.
+///
+/// ```rust
+/// let mut generator = Generator::new(42);
+/// let board: Board<4> = generator.random_solvable();
+/// assert!(board.is_solvable());
+/// let board: Option<Board<3>> = generator.at_distance(12, 100);
+/// ```
+pub struct Generator {
+    /// State of the SplitMix64 pseudo-random number generator.
+    state: u64,
+}
+
+impl Generator {
+    /// Creates a generator from a seed.
+    pub fn new(seed: u64) -> Generator {
+        Generator { state: seed }
+    }
+
+    /// Returns the next pseudo-random number of the sequence (SplitMix64).
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a pseudo-random number uniformly distributed in `0..bound`.
+    pub fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "empty range");
+        // reject the last incomplete range of values, which would be slightly less likely
+        let bound = bound as u64;
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return (value % bound) as usize;
+            }
+        }
+    }
+
+    /// Returns a board drawn uniformly among all solvable boards.
+    ///
+    /// A random permutation of the cells is drawn, and two tiles are swapped if it is not solvable.
+    /// Since swapping the first two tiles maps the unsolvable permutations one-to-one onto the solvable ones,
+    /// each solvable board has the same probability to be generated.
+    pub fn random_solvable<const N: usize>(&mut self) -> Board<N> {
+        let mut values: Vec<Cell> = (0..(N * N) as Cell).collect();
+        // Fisher-Yates shuffle
+        for i in (1..values.len()).rev() {
+            let j = self.below(i + 1);
+            values.swap(i, j);
+        }
+        let mut board = to_board::<N>(&values);
+        if !board.is_solvable() {
+            let mut tiles = (0..values.len()).filter(|&i| values[i] != EMPTY_CELL);
+            let (first, second) = (tiles.next().unwrap(), tiles.next().unwrap());
+            values.swap(first, second);
+            board = to_board(&values);
+        }
+        board
+    }
+
+    /// Returns the board reached from the goal by a random walk of the given number of moves.
+    /// The walk never goes back to a board it already visited, unless it is stuck.
+    pub fn random_walk<const N: usize>(&mut self, moves: usize) -> Board<N> {
+        let mut board = Board::GOAL;
+        let mut visited = vec![board];
+        for _ in 0..moves {
+            let successors: Vec<Board<N>> =
+                DIRECTIONS.iter().filter_map(|&d| board.apply(d)).collect();
+            let fresh: Vec<Board<N>> = successors
+                .iter()
+                .copied()
+                .filter(|s| !visited.contains(s))
+                .collect();
+            board = if fresh.is_empty() {
+                successors[self.below(successors.len())]
+            } else {
+                fresh[self.below(fresh.len())]
+            };
+            visited.push(board);
+        }
+        board
+    }
+
+    /// Returns a board whose optimal plan has exactly `distance` moves.
+    ///
+    /// Candidates are obtained by random walks of `distance` moves from the goal, and are kept only if an optimal
+    /// solver (IDA* with the linear-conflict heuristic) confirms that they cannot be solved in fewer moves.
+    /// Returns `None` if no candidate was accepted after `max_attempts` walks, which is the case
+    /// for distances close to the diameter of the state space (e.g. 31 for the 8-puzzle).
+    pub fn at_distance<const N: usize>(
+        &mut self,
+        distance: u32,
+        max_attempts: usize,
+    ) -> Option<Board<N>> {
+        for _ in 0..max_attempts {
+            let board = self.random_walk::<N>(distance as usize);
+            let (outcome, _) = ida_star(board, &Board::GOAL, &Heuristic::LinearConflict);
+            if outcome.plan().map(|plan| plan.len() as u32) == Some(distance) {
+                return Some(board);
+            }
+        }
+        None
+    }
+}
+
+/// Builds a board from its cells, given line by line.
+fn to_board<const N: usize>(values: &[Cell]) -> Board<N> {
+    let mut cells = [[EMPTY_CELL; N]; N];
+    for (i, &value) in values.iter().enumerate() {
+        cells[i / N][i % N] = value;
+    }
+    Board::new(cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reproducible() {
+        let mut first = Generator::new(7);
+        let mut second = Generator::new(7);
+        for _ in 0..10 {
+            assert_eq!(first.random_solvable::<4>(), second.random_solvable::<4>());
+            assert_eq!(
+                first.at_distance::<3>(8, 100),
+                second.at_distance::<3>(8, 100)
+            );
+        }
+        assert_ne!(
+            Generator::new(1).random_solvable::<4>(),
+            Generator::new(2).random_solvable::<4>()
+        );
+    }
+
+    #[test]
+    fn test_random_solvable() {
+        let mut generator = Generator::new(0);
+        let mut blank_positions = [[0; 3]; 3];
+        for _ in 0..900 {
+            let board: Board<3> = generator.random_solvable();
+            assert!(board.is_solvable());
+            // every value appears exactly once
+            let mut seen = [false; 9];
+            for x in 0..3 {
+                for y in 0..3 {
+                    seen[board.value_at(x, y) as usize] = true;
+                }
+            }
+            assert!(seen.iter().all(|&s| s));
+            let (x, y) = board.position(EMPTY_CELL);
+            blank_positions[x][y] += 1;
+        }
+        // the empty cell is equally likely to be anywhere (100 times on average)
+        assert!(blank_positions
+            .iter()
+            .flatten()
+            .all(|&count| (50..150).contains(&count)));
+
+        assert!(generator.random_solvable::<5>().is_solvable());
+    }
+
+    #[test]
+    fn test_at_distance() {
+        let mut generator = Generator::new(3);
+        for distance in 0..15 {
+            let board: Board<3> = generator
+                .at_distance(distance, 1000)
+                .expect("no board found");
+            let (outcome, _) = search(board, &Board::GOAL, &Heuristic::Manhattan);
+            assert_eq!(outcome.plan().map(|p| p.len() as u32), Some(distance));
+        }
+        // no 8-puzzle board is that far from the goal
+        assert_eq!(generator.at_distance::<3>(40, 10), None);
+
+        let board: Board<4> = generator.at_distance(20, 100).expect("no board found");
+        assert_eq!(
+            ida_star(board, &Board::GOAL, &Heuristic::LinearConflict)
+                .1
+                .solution_cost,
+            Some(20)
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use crate::board::*;
+
+/// A goal board, with the goal position of every tile precomputed so that heuristics can find it in constant time.
+///
+/// ```rust
+/// let goal = Goal::new(Board::new([[0, 1, 2], [3, 4, 5], [6, 7, 8]]));
+/// assert_eq!(goal.position(5), (1, 2));
+/// assert_eq!(Goal::<3>::STANDARD.position(EMPTY_CELL), (2, 2));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Goal<const N: usize> {
+    board: Board<N>,
+    /// Position `(line, column)` of each tile in the goal board, the one of `tile` being at `[tile / N][tile % N]`.
+    positions: [[(u8, u8); N]; N],
+}
+
+impl<const N: usize> Goal<N> {
+    /// The usual goal `Board::GOAL`, with the empty cell in the bottom-right corner.
+    pub const STANDARD: Goal<N> = Goal::new(Board::GOAL);
+
+    /// Creates the goal corresponding to the given board, which must contain each value of `0..N*N` exactly once.
+    pub const fn new(board: Board<N>) -> Goal<N> {
+        let mut positions = [[(0, 0); N]; N];
+        let mut line = 0;
+        while line < N {
+            let mut column = 0;
+            while column < N {
+                let tile = board.value_at(line, column) as usize;
+                positions[tile / N][tile % N] = (line as u8, column as u8);
+                column += 1;
+            }
+            line += 1;
+        }
+        Goal { board, positions }
+    }
+
+    /// The goal board.
+    pub fn board(&self) -> &Board<N> {
+        &self.board
+    }
+
+    /// Returns the position `(line, column)` of the given cell value in the goal board.
+    pub fn position(&self, value: Cell) -> (usize, usize) {
+        let value = value as usize;
+        let (line, column) = self.positions[value / N][value % N];
+        (line as usize, column as usize)
+    }
+}
+
+impl<const N: usize> Default for Goal<N> {
+    fn default() -> Goal<N> {
+        Goal::STANDARD
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_goal_positions() {
+        for goal in [
+            Board8::GOAL,
+            Board::blank_first_goal(),
+            Board::spiral_goal(),
+        ] {
+            let lookup = Goal::new(goal);
+            assert_eq!(lookup.board(), &goal);
+            for tile in 0..9 {
+                assert_eq!(lookup.position(tile), goal.position(tile));
+            }
+        }
+        let goal = Goal::<5>::default();
+        for tile in 0..25 {
+            assert_eq!(goal.position(tile), Board24::GOAL.position(tile));
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use crate::board::*;
+use crate::goal::*;
+use crate::heuristics::*;
+
+/// Maximal number of counterexamples kept in a report, the other violations are only counted.
+const MAX_COUNTEREXAMPLES: usize = 10;
+
+/// A board on which a heuristic breaks one of its guarantees.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Counterexample<const N: usize> {
+    /// The estimate is larger than the exact distance to the goal: the heuristic is not admissible.
+    Overestimate {
+        board: Board<N>,
+        estimate: u32,
+        distance: u32,
+    },
+    /// The estimates of two neighbouring boards differ by more than the cost of a move: the heuristic is not consistent.
+    Inconsistent {
+        board: Board<N>,
+        successor: Board<N>,
+        estimate: u32,
+        successor_estimate: u32,
+    },
+}
+
+impl<const N: usize> std::fmt::Display for Counterexample<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Counterexample::Overestimate {
+                board,
+                estimate,
+                distance,
+            } => write!(
+                f,
+                "{} is estimated at {estimate} but is at distance {distance}",
+                board.to_compact_string()
+            ),
+            Counterexample::Inconsistent {
+                board,
+                successor,
+                estimate,
+                successor_estimate,
+            } => write!(
+                f,
+                "{} is estimated at {estimate} but its neighbour {} at {successor_estimate}",
+                board.to_compact_string(),
+                successor.to_compact_string()
+            ),
+        }
+    }
+}
+
+/// The result of checking a heuristic on a set of boards.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HeuristicReport<const N: usize> {
+    /// Number of boards whose estimate was compared to their distance.
+    pub boards: usize,
+    /// Number of moves (from the checked boards) whose estimates were compared.
+    pub edges: usize,
+    /// Number of boards whose estimate exceeds their distance.
+    pub overestimates: usize,
+    /// Number of moves along which the estimate changes by more than 1.
+    pub inconsistencies: usize,
+    /// The first violations found.
+    pub counterexamples: Vec<Counterexample<N>>,
+}
+
+impl<const N: usize> HeuristicReport<N> {
+    /// Returns `true` if no checked board was overestimated.
+    pub fn is_admissible(&self) -> bool {
+        self.overestimates == 0
+    }
+
+    /// Returns `true` if the estimate never changed by more than 1 along a checked move.
+    pub fn is_consistent(&self) -> bool {
+        self.inconsistencies == 0
+    }
+
+    fn record(&mut self, counterexample: Counterexample<N>) {
+        match counterexample {
+            Counterexample::Overestimate { .. } => self.overestimates += 1,
+            Counterexample::Inconsistent { .. } => self.inconsistencies += 1,
+        }
+        if self.counterexamples.len() < MAX_COUNTEREXAMPLES {
+            self.counterexamples.push(counterexample);
+        }
+    }
+}
+
+/// Checks the heuristic towards `goal` on the given boards, whose exact distance to the goal must be known:
+/// every board of a `DistanceTable` (see `DistanceTable::boards`), or a sample of boards solved optimally.
+///
+/// A heuristic is admissible if it never overestimates the distance, i.e., `h(b) <= h*(b)`,
+/// and consistent if `|h(b) - h(b')| <= 1` for every move from `b` to `b'`. Consistency is checked on
+/// all the moves from the given boards.
+pub fn check_heuristic<const N: usize>(
+    heuristic: &Heuristic,
+    goal: &Board<N>,
+    boards: impl IntoIterator<Item = (Board<N>, u32)>,
+) -> HeuristicReport<N> {
+    let goal = Goal::new(*goal);
+    check_estimates(|board| heuristic.estimate_towards(board, &goal), boards)
+}
+
+/// Checks the admissibility and consistency of any estimate of the distance to the goal, see `check_heuristic`.
+pub fn check_estimates<const N: usize>(
+    estimate: impl Fn(&Board<N>) -> u32,
+    boards: impl IntoIterator<Item = (Board<N>, u32)>,
+) -> HeuristicReport<N> {
+    let mut report = HeuristicReport::default();
+    for (board, distance) in boards {
+        report.boards += 1;
+        let h = estimate(&board);
+        if h > distance {
+            report.record(Counterexample::Overestimate {
+                board,
+                estimate: h,
+                distance,
+            });
+        }
+        for direction in DIRECTIONS {
+            if let Some(successor) = board.apply(direction) {
+                report.edges += 1;
+                let successor_h = estimate(&successor);
+                if h.abs_diff(successor_h) > 1 {
+                    report.record(Counterexample::Inconsistent {
+                        board,
+                        successor,
+                        estimate: h,
+                        successor_estimate: successor_h,
+                    });
+                }
+            }
+        }
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distance_table::*;
+    use crate::generator::*;
+    use crate::pattern_database::*;
+    use crate::search::*;
+    use std::sync::Arc;
+
+    /// Checks every heuristic variant on a sample of the boards of width `N`, one every `step` boards,
+    /// and returns the number of boards checked.
+    fn check_all<const N: usize>(goal: &Board<N>, groups: &[Vec<Cell>], step: usize) -> usize {
+        let table = Arc::new(DistanceTable::build_towards(goal));
+        let samples: Vec<(Board<N>, u32)> = table.boards().step_by(step).collect();
+        let pdb = PatternDatabase::build_towards(groups, goal);
+        for heuristic in [
+            Heuristic::Blind,
+            Heuristic::Hamming,
+            Heuristic::Manhattan,
+            Heuristic::LinearConflict,
+            Heuristic::PatternDatabase(Arc::new(pdb)),
+            Heuristic::DistanceTable(table.clone()),
+        ] {
+            let report = check_heuristic(&heuristic, goal, samples.iter().copied());
+            assert_eq!(report.boards, samples.len());
+            assert!(report.edges >= 2 * report.boards);
+            assert!(
+                report.is_admissible(),
+                "{heuristic:?}: {}",
+                report.counterexamples[0]
+            );
+            // see `test_pattern_database_inconsistency`
+            if !matches!(heuristic, Heuristic::PatternDatabase(_)) {
+                assert!(
+                    report.is_consistent(),
+                    "{heuristic:?}: {}",
+                    report.counterexamples[0]
+                );
+            }
+        }
+        samples.len()
+    }
+
+    #[test]
+    fn test_all_heuristics() {
+        // a twentieth of the state space is enough to cover all kinds of boards
+        let groups = [vec![1, 2, 3, 4], vec![5, 6, 7, 8]];
+        assert_eq!(check_all(&Board8::GOAL, &groups, 20), 9_072);
+        // the whole state space of the 3-puzzle, towards another goal
+        let goal = Board::new([[0, 1], [2, 3]]);
+        assert_eq!(check_all(&goal, &[vec![1, 2], vec![3]], 1), 12);
+    }
+
+    #[test]
+    fn test_pattern_database_inconsistency() {
+        // with the empty cell walled in the corner by 1 and 4, the group {1, 2, 3, 4} needs 3 more moves
+        // than once 1 has moved into the corner
+        let pdb = PatternDatabase::build::<3>(&[vec![1, 2, 3, 4], vec![5, 6, 7, 8]]);
+        let heuristic = Heuristic::PatternDatabase(Arc::new(pdb));
+        let board = Board::new([[0, 1, 2], [4, 3, 5], [6, 8, 7]]);
+        let report = check_heuristic(&heuristic, &Board::GOAL, [(board, 18)]);
+        assert!(report.is_admissible());
+        assert_eq!(
+            report.counterexamples,
+            vec![Counterexample::Inconsistent {
+                board,
+                successor: Board::new([[1, 0, 2], [4, 3, 5], [6, 8, 7]]),
+                estimate: 18,
+                successor_estimate: 15,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_larger_boards() {
+        // boards of the 15-puzzle solved optimally
+        let mut generator = Generator::new(3);
+        let samples: Vec<(Board15, u32)> = (0..10)
+            .map(|_| {
+                let board = generator.random_walk(20);
+                let (outcome, _) = ida_star(board, &Board::GOAL, &Heuristic::LinearConflict);
+                (board, outcome.plan().unwrap().len() as u32)
+            })
+            .collect();
+        let pdb = PatternDatabase::build::<4>(&[vec![1, 2, 3], vec![5, 6, 9], vec![13, 14, 15]]);
+        for heuristic in [
+            Heuristic::Hamming,
+            Heuristic::Manhattan,
+            Heuristic::LinearConflict,
+            Heuristic::PatternDatabase(Arc::new(pdb)),
+        ] {
+            let report = check_heuristic(&heuristic, &Board::GOAL, samples.iter().copied());
+            assert_eq!(report.boards, 10);
+            assert!(report.is_admissible() && report.is_consistent());
+        }
+    }
+
+    #[test]
+    fn test_counterexamples() {
+        let table = DistanceTable::build::<2>();
+        // twice the Manhattan distance overestimates, and changes by 2 on every move
+        let report = check_estimates(
+            |board| 2 * Heuristic::Manhattan.estimate(board),
+            table.boards::<2>(),
+        );
+        assert!(!report.is_admissible());
+        assert!(!report.is_consistent());
+        assert_eq!(report.inconsistencies, report.edges);
+        assert_eq!(report.counterexamples.len(), MAX_COUNTEREXAMPLES);
+
+        // admissible but not consistent: the exact distance, but only on even distances
+        let report = check_estimates(
+            |board: &Board<2>| table.distance(board).unwrap() / 2 * 2,
+            table.boards(),
+        );
+        assert!(report.is_admissible());
+        assert!(!report.is_consistent());
+        let counterexample = report.counterexamples[0];
+        assert!(matches!(
+            counterexample,
+            Counterexample::Inconsistent {
+                estimate,
+                successor_estimate,
+                ..
+            } if estimate.abs_diff(successor_estimate) == 2
+        ));
+        assert!(counterexample.to_string().contains("but its neighbour"));
+    }
+}
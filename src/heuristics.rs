@@ -1,12 +1,20 @@
 use crate::board::*;
+use crate::distance_table::*;
+use crate::goal::*;
+use crate::pattern_database::*;
+use std::sync::Arc;
 
 /// A heuristic function to estimate the cost of reaching the goal state from a given board.
 ///
 /// ```rust
 /// let board = Board::new([[8, 7, 3], [2, 0, 5], [1, 4, 6]]);
 /// let h = Heuristic::Manhattan.estimate(&board);
+///
+/// // towards another goal, whose tile positions are computed once
+/// let goal = Goal::new(Board::blank_first_goal());
+/// let h = Heuristic::Manhattan.estimate_towards(&board, &goal);
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Heuristic {
     /// The blind heuristic always returns 0.
     Blind,
@@ -14,43 +22,183 @@ pub enum Heuristic {
     Hamming,
     /// The Manhattan heuristic, which computes the sum of the Manhattan distances of each tile to its goal position.
     Manhattan,
+    /// The Manhattan heuristic, plus two moves for each tile that must leave its line (row or column)
+    /// to let another tile of the same line pass.
+    ///
+    /// Two tiles are in linear conflict if they are both in their goal line but in the reverse order.
+    /// In each line, the tiles that must leave it are the fewest tiles whose removal resolves every conflict.
+    LinearConflict,
+    /// An additive pattern database, shared between all the users of the heuristic.
+    ///
+    /// Panics when estimating boards of a different width or towards a different goal than the ones of the database.
+    PatternDatabase(Arc<PatternDatabase>),
+    /// The exact distance to the goal, looked up in a table of the whole state space.
+    ///
+    /// Panics when estimating boards of a different width or towards a different goal than the ones of the table.
+    DistanceTable(Arc<DistanceTable>),
 }
 
 impl Heuristic {
-    pub fn estimate(&self, board: &Board) -> u32 {
+    /// Estimates the cost of reaching `Board::GOAL` from the board.
+    pub fn estimate<const N: usize>(&self, board: &Board<N>) -> u32 {
+        self.estimate_towards(board, &Goal::STANDARD)
+    }
+
+    /// Estimates the cost of reaching the given goal from the board.
+    pub fn estimate_towards<const N: usize>(&self, board: &Board<N>, goal: &Goal<N>) -> u32 {
         match self {
             // blind heuristic always returns 0
             Heuristic::Blind => 0,
             Heuristic::Hamming => {
                 let mut hamming = 0;
-                for i in 0..3 {
-                    for j in 0..3 {
-                        if Board::GOAL.value_at(i, j) != board.value_at(i, j) {
-                            if board.value_at(i, j) == 0 {
+                for i in 0..N {
+                    for j in 0..N {
+                        if goal.board().value_at(i, j) != board.value_at(i, j) {
+                            if board.value_at(i, j) == EMPTY_CELL {
                                 continue;
                             } else {
-                                hamming = hamming + 1;
+                                hamming += 1;
                             }
                         }
                     }
                 }
                 hamming
             }
-            Heuristic::Manhattan => {
-                let mut manhattan = 0;
-                for i in 1..9 {
-                    let (x, y) = board.position(i);
-                    let (x_goal, y_goal) = Board::GOAL.position(i);
-                    manhattan = manhattan
-                        + (x_goal as i32 - x as i32).abs()
-                        + (y_goal as i32 - y as i32).abs();
-                }
-                manhattan.try_into().unwrap() 
+            Heuristic::Manhattan => manhattan(board, goal),
+            Heuristic::LinearConflict => manhattan(board, goal) + 2 * linear_conflicts(board, goal),
+            Heuristic::PatternDatabase(pdb) => pdb.estimate_towards(board, goal.board()),
+            Heuristic::DistanceTable(table) => table.estimate_towards(board, goal.board()),
+        }
+    }
+
+    /// The same heuristic, able to estimate the cost of reaching `goal`: the pattern databases and distance tables
+    /// built for another goal are built again towards this one (with the same groups of tiles), the other
+    /// heuristics work for any goal.
+    pub fn for_goal<const N: usize>(&self, goal: &Board<N>) -> Heuristic {
+        match self {
+            Heuristic::PatternDatabase(pdb) if !pdb.has_goal(goal) => Heuristic::PatternDatabase(
+                Arc::new(PatternDatabase::build_towards(pdb.groups(), goal)),
+            ),
+            Heuristic::DistanceTable(table) if !table.has_goal(goal) => {
+                Heuristic::DistanceTable(Arc::new(DistanceTable::build_towards(goal)))
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Estimates the cost of reaching the goal from `successor`, the board obtained by moving the empty cell of `board`
+    /// in `direction`, knowing the estimate `h` of `board` towards the same goal, and the position `blank` of the empty
+    /// cell of `board` (that callers track with `Direction::step` rather than searching it again).
+    ///
+    /// Only the contribution of the moved tile is updated (with the conflicts of the two lines it leaves and enters
+    /// for `LinearConflict`, or the cost of its group for `PatternDatabase`), which is cheaper than a fresh estimate.
+    pub fn estimate_after_move<const N: usize>(
+        &self,
+        board: &Board<N>,
+        blank: (usize, usize),
+        h: u32,
+        direction: Direction,
+        successor: &Board<N>,
+        goal: &Goal<N>,
+    ) -> u32 {
+        // the moved tile leaves the cell where the empty cell arrives, for the cell where the empty cell was
+        let (from, to) = (direction.step(blank), blank);
+        let tile = board.value_at(from.0, from.1);
+        let target = goal.position(tile);
+        let distance =
+            |(x, y): (usize, usize)| (x.abs_diff(target.0) + y.abs_diff(target.1)) as u32;
+        match self {
+            Heuristic::Hamming => h + (to != target) as u32 - (from != target) as u32,
+            Heuristic::Manhattan => h + distance(to) - distance(from),
+            Heuristic::LinearConflict => {
+                // a vertical move changes the rows of the tile, but not the order of its column (and conversely)
+                let conflicts = |board: &Board<N>| {
+                    if from.0 == to.0 {
+                        column_conflicts(board, goal, from.1) + column_conflicts(board, goal, to.1)
+                    } else {
+                        row_conflicts(board, goal, from.0) + row_conflicts(board, goal, to.0)
+                    }
+                };
+                h + distance(to) + 2 * conflicts(successor) - distance(from) - 2 * conflicts(board)
+            }
+            Heuristic::PatternDatabase(pdb) => pdb.estimate_after_move(h, tile, from, successor),
+            // nothing to update
+            Heuristic::Blind | Heuristic::DistanceTable(_) => {
+                self.estimate_towards(successor, goal)
             }
         }
     }
 }
 
+/// Sum of the Manhattan distances of each tile to its goal position.
+fn manhattan<const N: usize>(board: &Board<N>, goal: &Goal<N>) -> u32 {
+    let mut manhattan = 0;
+    for x in 0..N {
+        for y in 0..N {
+            let tile = board.value_at(x, y);
+            if tile != EMPTY_CELL {
+                let (x_goal, y_goal) = goal.position(tile);
+                manhattan += x_goal.abs_diff(x) + y_goal.abs_diff(y);
+            }
+        }
+    }
+    manhattan as u32
+}
+
+/// Number of tiles that must leave their goal line to resolve all linear conflicts, summed over all rows and columns.
+fn linear_conflicts<const N: usize>(board: &Board<N>, goal: &Goal<N>) -> u32 {
+    (0..N)
+        .map(|line| row_conflicts(board, goal, line) + column_conflicts(board, goal, line))
+        .sum()
+}
+
+/// Number of tiles that must leave the given row to resolve its linear conflicts.
+fn row_conflicts<const N: usize>(board: &Board<N>, goal: &Goal<N>, row: usize) -> u32 {
+    // goal columns of the tiles of the row that belong to this row, from left to right
+    let mut columns = Vec::with_capacity(N);
+    for y in 0..N {
+        let tile = board.value_at(row, y);
+        if tile != EMPTY_CELL {
+            let (x_goal, y_goal) = goal.position(tile);
+            if x_goal == row {
+                columns.push(y_goal);
+            }
+        }
+    }
+    // the tiles that can stay are the largest subset already in the right order
+    (columns.len() - longest_increasing_subsequence(&columns)) as u32
+}
+
+/// Number of tiles that must leave the given column to resolve its linear conflicts.
+fn column_conflicts<const N: usize>(board: &Board<N>, goal: &Goal<N>, column: usize) -> u32 {
+    // goal rows of the tiles of the column that belong to this column, from top to bottom
+    let mut rows = Vec::with_capacity(N);
+    for x in 0..N {
+        let tile = board.value_at(x, column);
+        if tile != EMPTY_CELL {
+            let (x_goal, y_goal) = goal.position(tile);
+            if y_goal == column {
+                rows.push(x_goal);
+            }
+        }
+    }
+    (rows.len() - longest_increasing_subsequence(&rows)) as u32
+}
+
+/// Length of the longest strictly increasing subsequence of `values`.
+fn longest_increasing_subsequence(values: &[usize]) -> usize {
+    // longest[i] is the length of the longest increasing subsequence ending with values[i]
+    let mut longest = vec![1; values.len()];
+    for i in 0..values.len() {
+        for j in 0..i {
+            if values[j] < values[i] {
+                longest[i] = longest[i].max(longest[j] + 1);
+            }
+        }
+    }
+    longest.into_iter().max().unwrap_or(0)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -61,5 +209,136 @@ mod tests {
         assert_eq!(Heuristic::Blind.estimate(&board), 0);
         assert_eq!(Heuristic::Hamming.estimate(&board), 7);
         assert_eq!(Heuristic::Manhattan.estimate(&board), 14);
+
+        let board = Board::new([[1, 2, 3, 4], [5, 6, 7, 8], [9, 10, 11, 12], [13, 0, 14, 15]]);
+        assert_eq!(Heuristic::Hamming.estimate(&board), 2);
+        assert_eq!(Heuristic::Manhattan.estimate(&board), 2);
+        assert_eq!(Heuristic::Manhattan.estimate(&Board24::GOAL), 0);
+    }
+
+    #[test]
+    fn test_linear_conflict() {
+        use super::*;
+        // 3 is in conflict with both 1 and 2, moving it out of the row resolves both conflicts
+        let board = Board::new([[3, 1, 2], [4, 5, 6], [7, 8, 0]]);
+        assert_eq!(Heuristic::Manhattan.estimate(&board), 4);
+        assert_eq!(Heuristic::LinearConflict.estimate(&board), 6);
+
+        // a fully reversed row: only one tile can stay, the two others must leave
+        let board = Board::new([[4, 3, 2, 1], [5, 6, 7, 8], [9, 10, 11, 12], [13, 14, 15, 0]]);
+        assert_eq!(Heuristic::Manhattan.estimate(&board), 8);
+        assert_eq!(Heuristic::LinearConflict.estimate(&board), 8 + 2 * 3);
+
+        // conflicts in a column: 7, 4 and 1 are fully reversed
+        let board = Board::new([[7, 2, 3], [4, 5, 6], [1, 8, 0]]);
+        assert_eq!(Heuristic::LinearConflict.estimate(&board), 4 + 2 * 2);
+
+        let board = Board::new([[8, 7, 3], [2, 0, 5], [1, 4, 6]]);
+        assert_eq!(Heuristic::LinearConflict.estimate(&board), 14);
+        assert_eq!(Heuristic::LinearConflict.estimate(&Board8::GOAL), 0);
+
+        // the heuristic dominates Manhattan but never exceeds the true cost
+        for (cost, board) in &INSTANCES {
+            let h = Heuristic::LinearConflict.estimate(board);
+            assert!(h >= Heuristic::Manhattan.estimate(board));
+            assert!(h <= *cost);
+        }
+    }
+
+    #[test]
+    fn test_other_goal() {
+        use super::*;
+        let goal = Goal::new(Board::blank_first_goal());
+        let board = Board::new([[1, 0, 2], [3, 4, 5], [6, 7, 8]]);
+        assert_eq!(Heuristic::Hamming.estimate_towards(&board, &goal), 1);
+        assert_eq!(Heuristic::Manhattan.estimate_towards(&board, &goal), 1);
+        assert_eq!(Heuristic::LinearConflict.estimate_towards(&board, &goal), 1);
+        assert_eq!(Heuristic::Manhattan.estimate(&board), 11);
+
+        // 2 and 1 are reversed in their goal row
+        let board = Board::new([[0, 2, 1], [3, 4, 5], [6, 7, 8]]);
+        assert_eq!(Heuristic::Manhattan.estimate_towards(&board, &goal), 2);
+        assert_eq!(Heuristic::LinearConflict.estimate_towards(&board, &goal), 4);
+
+        let goal = Goal::new(Board15::spiral_goal());
+        assert_eq!(
+            Heuristic::Manhattan.estimate_towards(goal.board(), &goal),
+            0
+        );
+        assert_eq!(
+            Heuristic::LinearConflict.estimate_towards(goal.board(), &goal),
+            0
+        );
+        assert_eq!(Heuristic::Hamming.estimate_towards(goal.board(), &goal), 0);
+    }
+
+    #[test]
+    fn test_estimate_after_move() {
+        use super::*;
+        use crate::generator::*;
+
+        /// Follows a random walk, checking that the updated estimate is always the fresh one.
+        fn check_walk<const N: usize>(heuristics: &[Heuristic], goal: &Board<N>, seed: u64) {
+            let goal = Goal::new(*goal);
+            let mut generator = Generator::new(seed);
+            for heuristic in heuristics {
+                let mut board: Board<N> = generator.random_solvable();
+                let mut h = heuristic.estimate_towards(&board, &goal);
+                let mut blank = board.position(EMPTY_CELL);
+                for _ in 0..300 {
+                    let direction = DIRECTIONS[generator.below(4)];
+                    let Some(successor) = board.apply(direction) else {
+                        continue;
+                    };
+                    h = heuristic
+                        .estimate_after_move(&board, blank, h, direction, &successor, &goal);
+                    blank = direction.step(blank);
+                    assert_eq!(
+                        h,
+                        heuristic.estimate_towards(&successor, &goal),
+                        "{heuristic:?}"
+                    );
+                    board = successor;
+                }
+            }
+        }
+
+        let pdb = PatternDatabase::build::<3>(&[vec![1, 2, 3], vec![4, 5, 6, 7]]);
+        let heuristics = [
+            Heuristic::Blind,
+            Heuristic::Hamming,
+            Heuristic::Manhattan,
+            Heuristic::LinearConflict,
+            Heuristic::PatternDatabase(Arc::new(pdb)),
+        ];
+        check_walk(&heuristics, &Board8::GOAL, 1);
+        check_walk(&heuristics[..4], &Board8::blank_first_goal(), 2);
+        check_walk(&heuristics[..4], &Board15::spiral_goal(), 3);
+        let pdb = PatternDatabase::build::<4>(&[vec![1, 2, 5], vec![3, 4, 7]]);
+        check_walk(
+            &[Heuristic::PatternDatabase(Arc::new(pdb))],
+            &Board15::GOAL,
+            4,
+        );
+    }
+
+    #[test]
+    fn test_linear_conflict_search() {
+        use super::*;
+        use crate::search::*;
+
+        let mut total_manhattan = 0;
+        let mut total_linear_conflict = 0;
+        for (cost, board) in &INSTANCES {
+            let (outcome, manhattan) = search(*board, &Board::GOAL, &Heuristic::Manhattan);
+            assert_eq!(outcome.plan().map(|p| p.len()), Some(*cost as usize));
+            let (outcome, linear_conflict) =
+                search(*board, &Board::GOAL, &Heuristic::LinearConflict);
+            assert_eq!(outcome.plan().map(|p| p.len()), Some(*cost as usize));
+            assert!(linear_conflict.expanded <= manhattan.expanded);
+            total_manhattan += manhattan.expanded;
+            total_linear_conflict += linear_conflict.expanded;
+        }
+        assert!(total_linear_conflict < total_manhattan);
     }
 }
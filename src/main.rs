@@ -1,9 +1,17 @@
 #![allow(unused)] // suppress warnings for unused code (there is plenty when you start)
 
 // declare other modules that are in other files and must be compiled
+mod bitset;
 mod board;
+mod cli;
+mod distance_table;
+mod generator;
+mod goal;
+mod heuristic_check;
 mod heuristics;
 mod min_heap;
+mod packed_board;
+mod pattern_database;
 mod search;
 
 // import the content of the modules
@@ -13,10 +21,6 @@ use search::*;
 use Direction::*;
 
 fn main() {
-    let mut board = Board::new([[1, 2, 3], [4, 8, 5], [0, 7, 6]]);
-    let plan = [Right, Up, Right, Down];
-
-    board.is_valid_plan(&plan);
-
-    board.play(&plan);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    std::process::exit(cli::run(&args));
 }
@@ -42,15 +42,26 @@ impl<State: Ord> MinHeap<State> {
         self.heap.pop().map(|node| node.state)
     }
 
+    /// Returns the state with the smallest f-value from the heap, together with its f-value.
+    /// Returns `None` if the heap is empty.
+    pub fn pop_with_priority(&mut self) -> Option<(State, u32)> {
+        self.heap.pop().map(|node| (node.state, node.priority.0))
+    }
+
     /// Returns `true` if the heap is empty.
     pub fn is_empty(&self) -> bool {
         self.heap.is_empty()
     }
 
-    /// Returns true
+    /// Returns the number of states in the heap (counting each insertion of a state separately).
     pub fn len(&self) -> usize {
         self.heap.len()
     }
+
+    /// Iterates over the states of the heap, in no particular order.
+    pub fn states(&self) -> impl Iterator<Item = &State> {
+        self.heap.iter().map(|node| &node.state)
+    }
 }
 
 #[derive(Eq, PartialEq, PartialOrd, Ord)]
@@ -81,5 +92,14 @@ mod tests {
         assert_eq!(heap.pop(), None);
         assert!(heap.is_empty());
         assert_eq!(heap.len(), 0);
+
+        heap.insert("seven", 7);
+        heap.insert("six", 6);
+        let mut states: Vec<_> = heap.states().copied().collect();
+        states.sort();
+        assert_eq!(states, vec!["seven", "six"]);
+        assert_eq!(heap.pop_with_priority(), Some(("six", 6)));
+        assert_eq!(heap.pop_with_priority(), Some(("seven", 7)));
+        assert_eq!(heap.pop_with_priority(), None);
     }
 }
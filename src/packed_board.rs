@@ -0,0 +1,158 @@
+use crate::board::*;
+
+/// A compact encoding of a board of width at most 4, that fits in a single `u64`.
+///
+/// Each cell takes 4 bits, line by line starting from the least significant bits, and the index of the empty cell is cached
+/// so that moves only require a few shifts and masks. With the cached index, a packed board takes 16 bytes, so sets and maps
+/// should rather store its 64-bit `code` (8 bytes per state, against 16 for a `Board<4>`), as the `ClosedList::Packed`
+/// closed list does, and recover the board with `from_code`.
+///
+/// ```rust
+/// let board = Board::new([[1, 2, 3], [4, 5, 6], [7, 0, 8]]);
+/// let packed = PackedBoard::from(board);
+/// assert_eq!(packed.blank(), (2, 1));
+/// assert_eq!(packed.apply(Direction::Right).map(Board::from), Some(Board::GOAL));
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct PackedBoard<const N: usize> {
+    /// The cells, 4 bits each.
+    tiles: u64,
+    /// Index `line * N + column` of the empty cell.
+    blank: u8,
+}
+
+/// Mask of the bits of a single cell.
+const CELL_MASK: u64 = 0xF;
+
+impl<const N: usize> PackedBoard<N> {
+    /// Returns `true` if boards of width `N` fit in a `u64`, i.e., for widths up to 4.
+    pub const fn fits() -> bool {
+        N * N <= 16
+    }
+
+    /// The code of a board (see `code`), without building the packed board.
+    ///
+    /// Panics if boards of width `N` do not fit in a `u64`.
+    pub fn encode(board: &Board<N>) -> u64 {
+        assert!(Self::fits(), "Packed boards are limited to 4x4");
+        let mut tiles = 0;
+        for line in 0..N {
+            for column in 0..N {
+                tiles |= (board.value_at(line, column) as u64) << (4 * (line * N + column));
+            }
+        }
+        tiles
+    }
+
+    /// Index of the empty cell, found by looking for the only nibble set to zero.
+    fn find_blank(tiles: u64) -> u8 {
+        (0..N * N)
+            .find(|&i| (tiles >> (4 * i)) & CELL_MASK == EMPTY_CELL as u64)
+            .expect("no empty cell") as u8
+    }
+
+    /// The 64-bit code of the board, from which it can be rebuilt with `from_code`.
+    pub fn code(&self) -> u64 {
+        self.tiles
+    }
+
+    /// Rebuilds a packed board from its code.
+    ///
+    /// Panics if boards of width `N` do not fit in a `u64` (checked at runtime, like `encode`, so that the searches
+    /// can choose between packed and plain boards for any width).
+    pub fn from_code(code: u64) -> PackedBoard<N> {
+        assert!(Self::fits(), "Packed boards are limited to 4x4");
+        PackedBoard {
+            tiles: code,
+            blank: Self::find_blank(code),
+        }
+    }
+
+    /// Returns the value of the cell at the given position.
+    pub fn value_at(&self, line: usize, column: usize) -> Cell {
+        ((self.tiles >> (4 * (line * N + column))) & CELL_MASK) as Cell
+    }
+
+    /// Returns the position `(line, column)` of the empty cell, in constant time.
+    pub fn blank(&self) -> (usize, usize) {
+        (self.blank as usize / N, self.blank as usize % N)
+    }
+
+    /// Returns the result of moving the empty cell in the given direction,
+    /// or `None` if it would move outside the board (same as `Board::apply`).
+    pub fn apply(&self, direction: Direction) -> Option<PackedBoard<N>> {
+        let (x, y) = self.blank();
+        let target = match direction {
+            Direction::Up if x > 0 => self.blank as usize - N,
+            Direction::Down if x < N - 1 => self.blank as usize + N,
+            Direction::Left if y > 0 => self.blank as usize - 1,
+            Direction::Right if y < N - 1 => self.blank as usize + 1,
+            _ => return None,
+        };
+        // the tile moves to the former empty cell, whose nibble is zero
+        let tile = (self.tiles >> (4 * target)) & CELL_MASK;
+        let tiles = (self.tiles & !(CELL_MASK << (4 * target))) | (tile << (4 * self.blank));
+        Some(PackedBoard {
+            tiles,
+            blank: target as u8,
+        })
+    }
+}
+
+impl<const N: usize> From<Board<N>> for PackedBoard<N> {
+    fn from(board: Board<N>) -> PackedBoard<N> {
+        PackedBoard::from_code(PackedBoard::encode(&board))
+    }
+}
+
+impl<const N: usize> From<PackedBoard<N>> for Board<N> {
+    fn from(packed: PackedBoard<N>) -> Board<N> {
+        let mut cells = [[EMPTY_CELL; N]; N];
+        for (line, cells) in cells.iter_mut().enumerate() {
+            for (column, cell) in cells.iter_mut().enumerate() {
+                *cell = packed.value_at(line, column);
+            }
+        }
+        Board::new(cells)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::*;
+
+    #[test]
+    fn test_round_trip() {
+        for (_, board) in &INSTANCES {
+            let packed = PackedBoard::from(*board);
+            assert_eq!(Board::from(packed), *board);
+            assert_eq!(packed.blank(), board.position(EMPTY_CELL));
+            assert_eq!(PackedBoard::from_code(packed.code()), packed);
+            assert_eq!(PackedBoard::encode(board), packed.code());
+        }
+        let packed = PackedBoard::from(Board15::GOAL);
+        assert_eq!(packed.code(), 0x0FED_CBA9_8765_4321);
+        assert_eq!(packed.value_at(3, 2), 15);
+        assert_eq!(Board::from(packed), Board15::GOAL);
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut generator = Generator::new(11);
+        for _ in 0..100 {
+            let mut board: Board<4> = generator.random_solvable();
+            let mut packed = PackedBoard::from(board);
+            for _ in 0..50 {
+                let direction = DIRECTIONS[generator.below(4)];
+                let next = packed.apply(direction);
+                assert_eq!(next.map(Board::from), board.apply(direction));
+                if let Some(next) = next {
+                    packed = next;
+                    board = Board::from(next);
+                    assert_eq!(packed.blank(), board.position(EMPTY_CELL));
+                }
+            }
+        }
+    }
+}
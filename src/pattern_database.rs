@@ -0,0 +1,609 @@
+use crate::bitset::*;
+use crate::board::*;
+use std::io::{Read, Write};
+use std::path::Path;
+
+/// An additive pattern database heuristic.
+///
+/// The tiles are partitioned into disjoint groups (the *patterns*). For each group, a table gives the minimal number of moves
+/// of the pattern tiles needed to bring them to their goal position, for every possible placement of the pattern tiles.
+/// Moves of the other tiles are free, so that the costs of the different groups can be summed
+/// while still never overestimating the true cost.
+///
+/// The heuristic is admissible but not always consistent: the cost of a placement is the minimum over the positions of
+/// the empty cell, so a single move can decrease the estimate by more than 1 when the empty cell is walled in
+/// by pattern tiles. A* then has to reopen some states.
+///
+/// ```rust
+/// let pdb = PatternDatabase::build::<3>(&[vec![1, 2, 3, 4], vec![5, 6, 7, 8]]);
+/// let board = Board::new([[8, 7, 3], [2, 0, 5], [1, 4, 6]]);
+/// let h = pdb.estimate(&board);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct PatternDatabase {
+    /// Width of the boards this database applies to.
+    width: usize,
+    /// Cells of the goal board, line by line.
+    goal: Vec<Cell>,
+    /// The disjoint groups of tiles.
+    groups: Vec<Vec<Cell>>,
+    /// For each group, the cost of every placement of its tiles, indexed by `placement_index`.
+    tables: Vec<Vec<u8>>,
+    /// For each tile, its group and its index in the group, if it belongs to one.
+    group_of: Vec<Option<(usize, usize)>>,
+}
+
+/// Marks a placement whose cost is not known yet when building the tables.
+const UNKNOWN: u8 = u8::MAX;
+
+/// Largest number of cells of the boards supported by pattern databases.
+const MAX_CELLS: usize = 32;
+
+/// Magic bytes at the start of a saved pattern database.
+const MAGIC: &[u8; 4] = b"PDB1";
+
+impl PatternDatabase {
+    /// Database made of the given groups and their tables, with the lookup of the group of each tile.
+    fn new(
+        width: usize,
+        goal: Vec<Cell>,
+        groups: Vec<Vec<Cell>>,
+        tables: Vec<Vec<u8>>,
+    ) -> PatternDatabase {
+        let mut group_of = vec![None; width * width];
+        for (g, group) in groups.iter().enumerate() {
+            for (i, &tile) in group.iter().enumerate() {
+                group_of[tile as usize] = Some((g, i));
+            }
+        }
+        PatternDatabase {
+            width,
+            goal,
+            groups,
+            tables,
+            group_of,
+        }
+    }
+
+    /// Builds the pattern database for the given disjoint groups of tiles of the `N x N` puzzle, towards `Board::GOAL`.
+    ///
+    /// See `build_towards` for details.
+    pub fn build<const N: usize>(groups: &[Vec<Cell>]) -> PatternDatabase {
+        PatternDatabase::build_towards(groups, &Board::<N>::GOAL)
+    }
+
+    /// Builds the pattern database for the given disjoint groups of tiles of the `N x N` puzzle, towards the given goal.
+    ///
+    /// Each table is computed by a breadth-first search backward from the goal, where only the moves of the
+    /// tiles of the group are counted. Tables have one entry per placement of the group tiles on the board,
+    /// i.e. `(N*N)! / (N*N - k)!` entries for a group of `k` tiles.
+    ///
+    /// Panics if the groups are not disjoint or contain a value that is not a tile of the puzzle,
+    /// or if the board is larger than `5 x 5`.
+    pub fn build_towards<const N: usize>(groups: &[Vec<Cell>], goal: &Board<N>) -> PatternDatabase {
+        assert!(
+            N * N <= MAX_CELLS,
+            "Boards larger than 5x5 are not supported"
+        );
+        let mut seen = vec![false; N * N];
+        for &tile in groups.iter().flatten() {
+            assert!(
+                tile != EMPTY_CELL && (tile as usize) < N * N,
+                "Not a tile of the puzzle: {tile}"
+            );
+            assert!(
+                !seen[tile as usize],
+                "Tile {tile} appears in several groups"
+            );
+            seen[tile as usize] = true;
+        }
+
+        let tables = groups
+            .iter()
+            .map(|group| build_table(group, goal))
+            .collect();
+        PatternDatabase::new(N, cells(goal), groups.to_vec(), tables)
+    }
+
+    /// Width of the boards this database applies to.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns `true` if the database was built towards the given goal.
+    pub fn has_goal<const N: usize>(&self, goal: &Board<N>) -> bool {
+        N == self.width && self.goal == cells(goal)
+    }
+
+    /// The disjoint groups of tiles of this database.
+    pub fn groups(&self) -> &[Vec<Cell>] {
+        &self.groups
+    }
+
+    /// Estimated cost of reaching `Board::GOAL`, see `estimate_towards`.
+    pub fn estimate<const N: usize>(&self, board: &Board<N>) -> u32 {
+        self.estimate_towards(board, &Board::GOAL)
+    }
+
+    /// Estimated cost of reaching the goal: the sum of the costs of each group.
+    ///
+    /// Panics if the board does not have the width, or the goal is not the one, the database was built for.
+    pub fn estimate_towards<const N: usize>(&self, board: &Board<N>, goal: &Board<N>) -> u32 {
+        assert_eq!(
+            N, self.width,
+            "Pattern database built for boards of width {}",
+            self.width
+        );
+        assert!(
+            (0..N * N).all(|i| goal.value_at(i / N, i % N) == self.goal[i]),
+            "Pattern database built for another goal"
+        );
+        // cell index of each tile, so that each tile is located without scanning the board
+        let mut cell_of = [0u8; 256];
+        for x in 0..N {
+            for y in 0..N {
+                cell_of[board.value_at(x, y) as usize] = (x * N + y) as u8;
+            }
+        }
+        let mut placement = Vec::new();
+        let mut total = 0;
+        for (group, table) in self.groups.iter().zip(&self.tables) {
+            placement.clear();
+            placement.extend(group.iter().map(|&tile| cell_of[tile as usize]));
+            total += table[placement_index(&placement, N * N)] as u32;
+        }
+        total
+    }
+
+    /// Estimated cost of reaching the goal from `successor`, obtained by moving `tile` from the cell `from`
+    /// of a board whose estimate is `estimate`: only the cost of the group of the moved tile is looked up again,
+    /// before and after the move.
+    ///
+    /// The goal is not checked, it must be the one used for the previous estimate.
+    pub fn estimate_after_move<const N: usize>(
+        &self,
+        estimate: u32,
+        tile: Cell,
+        from: (usize, usize),
+        successor: &Board<N>,
+    ) -> u32 {
+        assert_eq!(
+            N, self.width,
+            "Pattern database built for boards of width {}",
+            self.width
+        );
+        let Some((moved_group, moved_index)) = self.group_of[tile as usize] else {
+            // the moves of the tiles outside of the groups are free
+            return estimate;
+        };
+        let table = &self.tables[moved_group];
+        let mut placement = [0u8; MAX_CELLS];
+        for x in 0..N {
+            for y in 0..N {
+                if let Some((group, i)) = self.group_of[successor.value_at(x, y) as usize] {
+                    if group == moved_group {
+                        placement[i] = (x * N + y) as u8;
+                    }
+                }
+            }
+        }
+        let placement = &mut placement[..self.groups[moved_group].len()];
+        let cost = table[placement_index(placement, N * N)] as u32;
+        // the placement before the move only differs by the cell of the moved tile
+        placement[moved_index] = (from.0 * N + from.1) as u8;
+        let previous_cost = table[placement_index(placement, N * N)] as u32;
+        estimate + cost - previous_cost
+    }
+
+    /// Saves the database to a binary file.
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        self.write_to(&mut file)?;
+        file.flush()
+    }
+
+    /// Loads a database previously written with `save`.
+    pub fn load(path: impl AsRef<Path>) -> std::io::Result<PatternDatabase> {
+        let mut file = std::io::BufReader::new(std::fs::File::open(path)?);
+        PatternDatabase::read_from(&mut file)
+    }
+
+    /// Writes the database in binary form: the magic bytes `PDB1`, the width and number of groups, the cells of the goal,
+    /// then for each group its tiles and its table (whose length is prefixed as a little-endian `u64`).
+    pub fn write_to(&self, out: &mut impl Write) -> std::io::Result<()> {
+        out.write_all(MAGIC)?;
+        out.write_all(&[self.width as u8, self.groups.len() as u8])?;
+        out.write_all(&self.goal)?;
+        for (group, table) in self.groups.iter().zip(&self.tables) {
+            out.write_all(&[group.len() as u8])?;
+            out.write_all(group)?;
+            out.write_all(&(table.len() as u64).to_le_bytes())?;
+            out.write_all(table)?;
+        }
+        Ok(())
+    }
+
+    /// Reads a database written with `write_to`.
+    pub fn read_from(input: &mut impl Read) -> std::io::Result<PatternDatabase> {
+        let invalid = |message: &str| std::io::Error::new(std::io::ErrorKind::InvalidData, message);
+
+        let mut magic = [0u8; 4];
+        input.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a pattern database file"));
+        }
+        let mut header = [0u8; 2];
+        input.read_exact(&mut header)?;
+        let [width, num_groups] = header.map(|b| b as usize);
+        if width * width > MAX_CELLS {
+            return Err(invalid("board too large"));
+        }
+        let mut goal = vec![0; width * width];
+        input.read_exact(&mut goal)?;
+        let mut sorted = goal.clone();
+        sorted.sort();
+        if sorted
+            .iter()
+            .enumerate()
+            .any(|(i, &cell)| cell as usize != i)
+        {
+            return Err(invalid("invalid goal"));
+        }
+
+        let mut groups = Vec::with_capacity(num_groups);
+        let mut tables = Vec::with_capacity(num_groups);
+        // the groups must be disjoint, as in `build_towards`
+        let mut seen = vec![false; width * width];
+        for _ in 0..num_groups {
+            let mut len = [0u8; 1];
+            input.read_exact(&mut len)?;
+            if len[0] as usize >= width * width {
+                return Err(invalid("group larger than the number of tiles"));
+            }
+            let mut group = vec![0; len[0] as usize];
+            input.read_exact(&mut group)?;
+            for &tile in &group {
+                if tile == EMPTY_CELL || tile as usize >= width * width {
+                    return Err(invalid("tile out of the board"));
+                }
+                if std::mem::replace(&mut seen[tile as usize], true) {
+                    return Err(invalid("tile in several groups"));
+                }
+            }
+
+            let mut len = [0u8; 8];
+            input.read_exact(&mut len)?;
+            let len = u64::from_le_bytes(len) as usize;
+            if Some(len) != checked_num_placements(group.len(), width * width) {
+                return Err(invalid("table size does not match the group"));
+            }
+            // read incrementally rather than allocating the whole table upfront, so that a truncated file
+            // declaring a huge group fails without allocating gigabytes first
+            let mut table = Vec::new();
+            input.by_ref().take(len as u64).read_to_end(&mut table)?;
+            if table.len() != len {
+                return Err(std::io::ErrorKind::UnexpectedEof.into());
+            }
+
+            groups.push(group);
+            tables.push(table);
+        }
+        Ok(PatternDatabase::new(width, goal, groups, tables))
+    }
+}
+
+// Only print the shape of the database, not its (large) tables.
+impl std::fmt::Debug for PatternDatabase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PatternDatabase")
+            .field("width", &self.width)
+            .field("goal", &self.goal)
+            .field("groups", &self.groups)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Cells of the board, line by line.
+fn cells<const N: usize>(board: &Board<N>) -> Vec<Cell> {
+    (0..N * N).map(|i| board.value_at(i / N, i % N)).collect()
+}
+
+/// Number of ways of placing `k` distinct tiles on `cells` cells: `cells! / (cells - k)!`.
+fn num_placements(k: usize, cells: usize) -> usize {
+    checked_num_placements(k, cells).expect("Group too large")
+}
+
+/// `num_placements`, or `None` if there are more tiles than cells or if the number does not fit in a `usize`.
+fn checked_num_placements(k: usize, cells: usize) -> Option<usize> {
+    (cells.checked_sub(k)? + 1..=cells).try_fold(1usize, |n, c| n.checked_mul(c))
+}
+
+/// Index of a placement (the cell of each tile of a group) in `0..num_placements(k, cells)`.
+///
+/// The placement is read as a number in a mixed radix: the i-th tile may be on any of the `cells - i` cells
+/// not occupied by the previous tiles.
+fn placement_index(placement: &[u8], cells: usize) -> usize {
+    let mut index = 0;
+    for (i, &cell) in placement.iter().enumerate() {
+        let free_before = cell as usize - placement[..i].iter().filter(|&&c| c < cell).count();
+        index = index * (cells - i) + free_before;
+    }
+    index
+}
+
+/// Inverse of `placement_index`: fills `placement` with the cells of the placement of the given index.
+fn placement_from_index(mut index: usize, cells: usize, placement: &mut [u8]) {
+    let k = placement.len();
+    // digits of the mixed radix number, starting from the last one
+    let mut digits = [0usize; MAX_CELLS];
+    for i in (0..k).rev() {
+        digits[i] = index % (cells - i);
+        index /= cells - i;
+    }
+    let mut used = [false; MAX_CELLS];
+    for i in 0..k {
+        // the digit is the rank of the cell among the free ones
+        let cell = (0..cells)
+            .filter(|&c| !used[c])
+            .nth(digits[i])
+            .expect("invalid placement index");
+        used[cell] = true;
+        placement[i] = cell as u8;
+    }
+}
+
+/// Computes the cost of every placement of the tiles of the group, by a breadth-first search backward from the goal.
+///
+/// The states of the search are the placements of the group together with the cell of the empty cell.
+/// Moving the empty cell onto a cell that holds no tile of the group is free, so the states are processed by cost levels:
+/// each state taken from a level is expanded together with all the states reachable from it for free, i.e., the whole
+/// region of the empty cell between the group tiles, and the moves of the group tiles from this region
+/// lead to the next level.
+///
+/// The processed states take one bit each (about 1 GB for a group of 8 tiles of the 15-puzzle, twice the table),
+/// and the states to process are stored as their index `placement * cells + blank`.
+fn build_table<const N: usize>(group: &[Cell], goal: &Board<N>) -> Vec<u8> {
+    let cells = N * N;
+    let k = group.len();
+    let mut table = vec![UNKNOWN; num_placements(k, cells)];
+    // the states `placement * cells + blank` already processed
+    let mut visited = BitSet::new(table.len() * cells);
+
+    let goal_placement: Vec<u8> = group
+        .iter()
+        .map(|&tile| {
+            let (x, y) = goal.position(tile);
+            (x * N + y) as u8
+        })
+        .collect();
+    let (x, y) = goal.position(EMPTY_CELL);
+
+    let mut cost: u8 = 0;
+    let mut current: Vec<u64> =
+        vec![(placement_index(&goal_placement, cells) * cells + x * N + y) as u64];
+    let mut next = Vec::new();
+    let mut placement = vec![0u8; k];
+    // the tile of the group on each cell, if any
+    let mut tile_on = [None; MAX_CELLS];
+    let mut region = Vec::with_capacity(cells);
+    while !current.is_empty() {
+        while let Some(state) = current.pop() {
+            let (index, blank) = (state as usize / cells, state as usize % cells);
+            if !visited.insert(index * cells + blank) {
+                continue;
+            }
+            if table[index] == UNKNOWN {
+                table[index] = cost;
+            }
+
+            placement_from_index(index, cells, &mut placement);
+            for (tile, &cell) in placement.iter().enumerate() {
+                tile_on[cell as usize] = Some(tile);
+            }
+            region.clear();
+            region.push(blank);
+            while let Some(blank) = region.pop() {
+                let (x, y) = (blank / N, blank % N);
+                let neighbours = [
+                    (x > 0).then(|| blank - N),
+                    (x < N - 1).then(|| blank + N),
+                    (y > 0).then(|| blank - 1),
+                    (y < N - 1).then(|| blank + 1),
+                ];
+                for target in neighbours.into_iter().flatten() {
+                    match tile_on[target] {
+                        // a tile of the group moves in place of the empty cell
+                        Some(tile) => {
+                            placement[tile] = blank as u8;
+                            let state = placement_index(&placement, cells) * cells + target;
+                            if !visited.contains(state) {
+                                next.push(state as u64);
+                            }
+                            placement[tile] = target as u8;
+                        }
+                        // another tile moves, for free
+                        None => {
+                            if visited.insert(index * cells + target) {
+                                region.push(target);
+                            }
+                        }
+                    }
+                }
+            }
+            for &cell in &placement {
+                tile_on[cell as usize] = None;
+            }
+        }
+        std::mem::swap(&mut current, &mut next);
+        cost += 1;
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::goal::*;
+    use crate::heuristics::*;
+    use crate::search::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_placement_index() {
+        let cells = 9;
+        let mut placement = [0u8; 3];
+        for index in 0..num_placements(3, cells) {
+            placement_from_index(index, cells, &mut placement);
+            assert_eq!(placement_index(&placement, cells), index);
+        }
+        assert_eq!(num_placements(3, cells), 9 * 8 * 7);
+        assert_eq!(placement_index(&[8, 7, 6], cells), 9 * 8 * 7 - 1);
+    }
+
+    #[test]
+    fn test_pattern_database() {
+        let pdb = PatternDatabase::build::<3>(&[vec![1, 2, 3, 4], vec![5, 6, 7, 8]]);
+        let heuristic = Heuristic::PatternDatabase(Arc::new(pdb));
+        assert_eq!(heuristic.estimate(&Board8::GOAL), 0);
+
+        for (cost, board) in &INSTANCES {
+            // admissible: never overestimates the known optimal cost
+            let h = heuristic.estimate(board);
+            assert!(h <= *cost, "h = {h} > {cost} for {board:?}");
+            // each group alone costs at least the Manhattan distance of its tiles, hence so does their sum
+            assert!(h >= Heuristic::Manhattan.estimate(board));
+
+            let (outcome, _) = search(*board, &Board::GOAL, &heuristic);
+            assert_eq!(outcome.plan().map(|p| p.len()), Some(*cost as usize));
+        }
+    }
+
+    #[test]
+    fn test_pattern_database_larger_boards() {
+        use Direction::*;
+        let pdb = PatternDatabase::build::<4>(&[
+            vec![1, 2, 5],
+            vec![3, 4, 8],
+            vec![6, 7, 11],
+            vec![9, 10, 13],
+            vec![12, 14, 15],
+        ]);
+        let heuristic = Heuristic::PatternDatabase(Arc::new(pdb));
+        let init = [
+            Up, Left, Left, Up, Right, Up, Left, Down, Left, Up, Right, Right, Down, Down, Left,
+            Left,
+        ]
+        .iter()
+        .fold(Board15::GOAL, |board, &d| board.apply(d).unwrap());
+        let (outcome, _) = ida_star(init, &Board::GOAL, &Heuristic::Manhattan);
+        let cost = outcome.plan().unwrap().len() as u32;
+        assert!(heuristic.estimate(&init) <= cost);
+        let (outcome, _) = ida_star(init, &Board::GOAL, &heuristic);
+        assert_eq!(outcome.plan().map(|p| p.len() as u32), Some(cost));
+    }
+
+    #[test]
+    fn test_six_tile_group() {
+        // 16! / 10! = 5,765,760 placements, the size class of the 6-6-3 and 7-8 partitions of the 15-puzzle
+        let group: Vec<Cell> = vec![1, 2, 3, 4, 5, 6];
+        let table = build_table(&group, &Board15::GOAL);
+        assert_eq!(table.len(), 16 * 15 * 14 * 13 * 12 * 11);
+        assert!(table.iter().all(|&cost| cost != UNKNOWN));
+        let pdb = PatternDatabase::new(4, cells(&Board15::GOAL), vec![group], vec![table]);
+        // the pattern costs are admissible, and at least the Manhattan distance of the group tiles
+        let mut generator = crate::generator::Generator::new(6);
+        for _ in 0..20 {
+            let board: Board15 = generator.random_walk(20);
+            let manhattan: u32 = (1..=6)
+                .map(|tile| {
+                    let ((x, y), (gx, gy)) = (board.position(tile), Board15::GOAL.position(tile));
+                    (x.abs_diff(gx) + y.abs_diff(gy)) as u32
+                })
+                .sum();
+            let (outcome, _) = ida_star(board, &Board::GOAL, &Heuristic::Manhattan);
+            let estimate = pdb.estimate(&board);
+            assert!(estimate >= manhattan);
+            assert!(estimate as usize <= outcome.plan().unwrap().len());
+        }
+    }
+
+    #[test]
+    fn test_other_goal() {
+        let goal = Board::blank_first_goal();
+        let pdb = PatternDatabase::build_towards(&[vec![1, 2, 3, 4], vec![5, 6, 7, 8]], &goal);
+        assert!(pdb.has_goal(&goal));
+        assert!(!pdb.has_goal(&Board8::GOAL));
+        let heuristic = Heuristic::PatternDatabase(Arc::new(pdb));
+        let lookup = Goal::new(goal);
+        assert_eq!(heuristic.estimate_towards(&goal, &lookup), 0);
+        for (_, board) in &INSTANCES {
+            // the instances are solved towards the usual goal, reverse them to get a board at the same distance of the other goal
+            let (outcome, _) = search(goal, board, &Heuristic::Manhattan);
+            let cost = outcome.plan().unwrap().len() as u32;
+            assert!(heuristic.estimate_towards(board, &lookup) <= cost);
+            assert!(
+                heuristic.estimate_towards(board, &lookup)
+                    >= Heuristic::Manhattan.estimate_towards(board, &lookup)
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "another goal")]
+    fn test_wrong_goal() {
+        let pdb = PatternDatabase::build::<3>(&[vec![1, 2, 3, 4], vec![5, 6, 7, 8]]);
+        pdb.estimate_towards(&Board8::GOAL, &Board::blank_first_goal());
+    }
+
+    #[test]
+    fn test_save_load() {
+        let pdb = PatternDatabase::build::<3>(&[vec![1, 2, 3], vec![4, 5, 6], vec![7, 8]]);
+        let mut bytes = Vec::new();
+        pdb.write_to(&mut bytes).unwrap();
+        let loaded = PatternDatabase::read_from(&mut bytes.as_slice()).unwrap();
+        assert_eq!(loaded, pdb);
+
+        let path = std::env::temp_dir().join(format!("puzzle-pdb-{}.bin", std::process::id()));
+        pdb.save(&path).unwrap();
+        let loaded = PatternDatabase::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, pdb);
+        assert_eq!(loaded.width(), 3);
+        assert_eq!(loaded.groups(), pdb.groups());
+
+        // corrupted files are rejected
+        assert!(PatternDatabase::read_from(&mut &bytes[1..]).is_err());
+        assert!(PatternDatabase::read_from(&mut &bytes[..bytes.len() - 1]).is_err());
+        // files of the 8-puzzle with the given groups, and tables of the right size
+        let file = |groups: &[&[Cell]]| {
+            let mut bytes = b"PDB1".to_vec();
+            bytes.extend([3, groups.len() as u8, 1, 2, 3, 4, 5, 6, 7, 8, 0]);
+            for group in groups {
+                bytes.push(group.len() as u8);
+                bytes.extend(*group);
+                let len = checked_num_placements(group.len(), 9).unwrap_or(0);
+                bytes.extend((len as u64).to_le_bytes());
+                bytes.extend(vec![0; len]);
+            }
+            bytes
+        };
+        let rejected = |bytes: Vec<u8>| {
+            let error = PatternDatabase::read_from(&mut bytes.as_slice()).unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        };
+        assert!(PatternDatabase::read_from(&mut file(&[&[1, 2], &[3]]).as_slice()).is_ok());
+        rejected(file(&[&[1, 2, 3, 4, 5, 6, 7, 8, 1, 2, 3, 4]]));
+        rejected(file(&[&[1, 1]]));
+        rejected(file(&[&[1, 2], &[2, 3]]));
+        rejected(file(&[&[0, 1]]));
+        rejected(file(&[&[9]]));
+        // a group of 8 tiles of the 15-puzzle (a 519 MB table) without its table
+        let mut bytes = b"PDB1".to_vec();
+        bytes.extend([4, 1]);
+        bytes.extend(cells(&Board15::GOAL));
+        bytes.extend([8, 1, 2, 3, 4, 5, 6, 7, 8]);
+        bytes.extend((checked_num_placements(8, 16).unwrap() as u64).to_le_bytes());
+        let error = PatternDatabase::read_from(&mut bytes.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -1,15 +1,51 @@
+use crate::bitset::*;
 use crate::board::*;
+use crate::goal::*;
 use crate::heuristics::*;
 use crate::min_heap::*;
+use crate::packed_board::*;
 use std::collections::*;
 use std::ops::Not;
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+mod bidirectional;
+mod depth_first;
+mod focal;
+mod greedy;
+mod ida;
+mod uninformed;
+pub use bidirectional::*;
+pub use depth_first::*;
+pub use focal::*;
+pub use greedy::*;
+pub use ida::*;
+pub use uninformed::*;
 
 /// Statistics of the search, used to evaluate the performance of the search algorithms.
 /// Feel free to add more fields to this struct if you need them.
+#[derive(Clone, Debug, Default)]
 pub struct Stats {
     /// Numbers of states expanded during search
     pub expanded: usize,
+    /// Number of successor states generated when expanding states.
+    pub generated: usize,
+    /// Number of generated states that had already been reached before.
+    pub duplicates: usize,
+    /// Number of already expanded states that were reached again through a cheaper path and had to be expanded again.
+    pub reopened: usize,
+    /// Largest number of entries in the open list during search.
+    pub peak_open: usize,
+    /// Largest number of states in the closed list during search.
+    pub peak_closed: usize,
+    /// Cost of the plan found, if any.
+    pub solution_cost: Option<u32>,
+    /// Proven upper bound on the ratio between the cost of the plan found and the optimal cost,
+    /// for the bounded-suboptimal algorithms (assuming an admissible heuristic).
+    pub suboptimality_bound: Option<f64>,
+    /// The successive f-layers of the search, in the order in which they were expanded.
+    pub layers: Vec<Layer>,
     /// Total runtime spend in the search.
     ///
     /// ```rust
@@ -20,86 +56,633 @@ pub struct Stats {
     pub runtime: Duration,
 }
 
+/// A group of consecutive expansions that share the same f-value.
+///
+/// For iterative deepening algorithms, there is one layer per iteration whose f-value is the threshold of the iteration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Layer {
+    /// The f-value of the states expanded in this layer.
+    pub f: u32,
+    /// Number of states expanded in this layer.
+    pub expanded: usize,
+}
+
 impl Stats {
     /// Creates a new `Stats` instance with the given expanded states count and runtime.
     pub fn new(expanded: usize, runtime: Duration) -> Stats {
-        Stats { expanded, runtime }
+        Stats {
+            expanded,
+            runtime,
+            ..Stats::default()
+        }
+    }
+
+    /// Records the expansion of a state with the given f-value.
+    fn record_expansion(&mut self, f: u32) {
+        self.expanded += 1;
+        match self.layers.last_mut() {
+            Some(layer) if layer.f == f => layer.expanded += 1,
+            _ => self.layers.push(Layer { f, expanded: 1 }),
+        }
+    }
+
+    /// Updates the peak sizes of the open and closed lists.
+    fn record_sizes(&mut self, open: usize, closed: usize) {
+        self.peak_open = self.peak_open.max(open);
+        self.peak_closed = self.peak_closed.max(closed);
+    }
+
+    /// The effective branching factor `b*`: the branching factor that a uniform tree of depth equal to
+    /// the solution cost would need to contain all generated states, i.e., `generated = b* + b*^2 + ... + b*^d`.
+    ///
+    /// Returns `None` if no plan was found or if the plan is empty.
+    pub fn effective_branching_factor(&self) -> Option<f64> {
+        let depth = self.solution_cost.filter(|&cost| cost > 0)?;
+        let target = self.generated.max(1) as f64;
+        let tree_size = |b: f64| (1..=depth).map(|d| b.powi(d as i32)).sum::<f64>();
+        // the tree size is increasing in b, find b* by bisection
+        let (mut low, mut high) = (0.0, target.max(1.0));
+        for _ in 0..100 {
+            let mid = (low + high) / 2.0;
+            if tree_size(mid) < target {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        Some((low + high) / 2.0)
     }
 }
 
-pub fn search(init_state: Board, heuristic: &Heuristic) -> (Option<Vec<Direction>>, Stats) {
-    let start = std::time::Instant::now();
-    // MinHeap provide allows to store the states to explore, with associated priority
-    let mut heap: MinHeap<Board> = MinHeap::new();
-    // the standard library provides a HashMap, that can be used to store the cost or other things
-    let mut costs: HashMap<Board, u32> = HashMap::new();
+// Compact one-line summary of the statistics, e.g.
+// `expanded=12 generated=30 duplicates=8 reopened=0 peak_open=11 peak_closed=12 cost=6 b*=1.41 f=4..6 runtime=42µs`
+impl std::fmt::Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "expanded={} generated={} duplicates={} reopened={} peak_open={} peak_closed={}",
+            self.expanded,
+            self.generated,
+            self.duplicates,
+            self.reopened,
+            self.peak_open,
+            self.peak_closed
+        )?;
+        match self.solution_cost {
+            Some(cost) => write!(f, " cost={cost}")?,
+            None => write!(f, " cost=-")?,
+        }
+        match self.effective_branching_factor() {
+            Some(b) => write!(f, " b*={b:.2}")?,
+            None => write!(f, " b*=-")?,
+        }
+        if let Some(bound) = self.suboptimality_bound {
+            write!(f, " bound={bound:.2}")?;
+        }
+        if let (Some(first), Some(last)) = (self.layers.first(), self.layers.last()) {
+            write!(f, " f={}..{}", first.f, last.f)?;
+        }
+        write!(f, " runtime={:?}", self.runtime)
+    }
+}
+
+/// The outcome of a search, telling whether a plan was found and, if not, why.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SearchOutcome {
+    /// A plan leading from the initial state to the goal (empty if the initial state is the goal).
+    Solved(Vec<Direction>),
+    /// The goal is not reachable from the initial state.
+    Unsolvable,
+    /// The search was stopped before finding a plan because it reached one of its limits.
+    ExhaustedLimit { limit: Limit },
+    /// The search was interrupted from the outside before finding a plan.
+    Cancelled,
+}
+
+impl SearchOutcome {
+    /// Returns the plan if one was found.
+    pub fn plan(&self) -> Option<&[Direction]> {
+        match self {
+            SearchOutcome::Solved(plan) => Some(plan),
+            _ => None,
+        }
+    }
 
-    let mut parent_action: HashMap<Board, (Board, Direction)> = HashMap::new();
+    /// Returns `true` if a plan was found.
+    pub fn is_solved(&self) -> bool {
+        matches!(self, SearchOutcome::Solved(_))
+    }
+}
 
-    let mut expanded: HashSet<Board> = HashSet::new();
+/// The resource limits that may stop a search before it finds a plan.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Limit {
+    /// The wall-clock deadline has passed.
+    Deadline,
+    /// The maximum number of expanded states was reached.
+    Expanded,
+    /// The maximum number of stored states was reached.
+    Stored,
+    /// The approximate memory budget was exceeded.
+    Memory,
+    /// The maximum depth of a depth-limited search was reached.
+    Depth,
+}
 
-    let mut path: HashSet<Board> = HashSet::new();
-    let mut directions: Vec<Direction> = Vec::new();
+/// A handle to cancel a search from another thread: all the clones of a token share the same flag, which the search
+/// checks periodically (see `SearchLimits::cancellation`).
+///
+/// ```rust
+/// let token = CancellationToken::new();
+/// let limits = SearchLimits::default().with_cancellation(token.clone());
+/// let search = std::thread::spawn(move || ida_star_with_limits(board, &Board::GOAL, &heuristic, &limits));
+/// token.cancel();
+/// let (outcome, stats) = search.join().unwrap(); // `SearchOutcome::Cancelled`, unless it was already solved
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
 
-    costs.insert(init_state, 0);
-    heap.insert(init_state, 0 + heuristic.estimate(&init_state));
+impl CancellationToken {
+    pub fn new() -> CancellationToken {
+        CancellationToken::default()
+    }
 
-    while !heap.is_empty() {
-        let mut s = heap.pop().expect("No node in the heap");
+    /// Asks the searches holding a clone of this token to stop. There is no way back.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
 
-        if expanded.contains(&s) {
-            continue;
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// The resource limits of a search, none by default. The `*_with_limits` variants of the search functions stop
+/// with `SearchOutcome::ExhaustedLimit` as soon as one of them is reached (or with `SearchOutcome::Cancelled` once
+/// their cancellation token is cancelled), the `Stats` telling how far they went (e.g., the f-value of the last layer
+/// is a lower bound on the optimal cost for the optimal algorithms).
+///
+/// ```rust
+/// let limits = SearchLimits {
+///     max_expanded: Some(100_000),
+///     ..SearchLimits::default()
+/// }
+/// .with_timeout(Duration::from_secs(1));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SearchLimits {
+    /// Instant after which the search stops (only checked every `CHECK_INTERVAL` expansions).
+    pub deadline: Option<Instant>,
+    /// Maximum number of states expanded.
+    pub max_expanded: Option<usize>,
+    /// Maximum number of states stored in the open and closed lists (or on the path for the depth-first searches).
+    pub max_stored: Option<usize>,
+    /// Maximum number of bytes used by the stored states, roughly estimated by `SearchLimits::approximate_bytes`.
+    pub max_bytes: Option<usize>,
+    /// Token to cancel the search from another thread (only checked every `CHECK_INTERVAL` expansions).
+    pub cancellation: Option<CancellationToken>,
+}
+
+impl SearchLimits {
+    /// Number of expansions between two checks of the deadline and of the cancellation token, reading the clock being
+    /// slower than expanding a state with the depth-first searches.
+    const CHECK_INTERVAL: usize = 1024;
+
+    /// Sets the deadline at `timeout` from now.
+    pub fn with_timeout(self, timeout: Duration) -> SearchLimits {
+        SearchLimits {
+            deadline: Some(Instant::now() + timeout),
+            ..self
         }
+    }
+
+    /// Makes the search stop once `token` is cancelled.
+    pub fn with_cancellation(self, token: CancellationToken) -> SearchLimits {
+        SearchLimits {
+            cancellation: Some(token),
+            ..self
+        }
+    }
+
+    /// Rough estimate of the memory used to store `stored` states of width `N`: each of them is usually kept as a key
+    /// of the maps of costs and parents, as the parent of another state and in the open list, plus the overhead
+    /// of the hash maps.
+    pub fn approximate_bytes<const N: usize>(stored: usize) -> usize {
+        stored * (4 * std::mem::size_of::<Board<N>>() + 32)
+    }
+
+    /// Returns the outcome of the search if it must stop before its next expansion (`SearchOutcome::ExhaustedLimit`
+    /// or `SearchOutcome::Cancelled`), given its statistics so far and the number of states currently stored.
+    ///
+    /// The searches only call it before each expansion, so the stored states may exceed their limit by the successors
+    /// of a single state.
+    fn stop<const N: usize>(&self, stats: &Stats, stored: usize) -> Option<SearchOutcome> {
+        let limit = if self.max_expanded.is_some_and(|max| stats.expanded >= max) {
+            Limit::Expanded
+        } else if self.max_stored.is_some_and(|max| stored > max) {
+            Limit::Stored
+        } else if self
+            .max_bytes
+            .is_some_and(|max| Self::approximate_bytes::<N>(stored) > max)
+        {
+            Limit::Memory
+        } else if !stats.expanded.is_multiple_of(Self::CHECK_INTERVAL) {
+            return None;
+        } else if self
+            .cancellation
+            .as_ref()
+            .is_some_and(|token| token.is_cancelled())
+        {
+            return Some(SearchOutcome::Cancelled);
+        } else if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            Limit::Deadline
+        } else {
+            return None;
+        };
+        Some(SearchOutcome::ExhaustedLimit { limit })
+    }
+}
 
-        if s == Board::GOAL {
-            let mut find: bool = false;
-            let mut parent: (Board, Direction);
-            while !find {
-                match parent_action.get(&s) {
-                    Some(x) => {
-                        parent = *x;
-                        path.insert(parent.0);
-                        directions.push(parent.1);
-                        s = parent.0;
-                        if parent.0 == init_state {
-                            find = true;
-                        }
-                    }
-                    None => find = true,
+/// How `search` stores the set of expanded states (and, for the first two, how it keys the costs and parents of the states).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClosedList {
+    /// A `HashSet` of boards, that works for any width.
+    Hashed,
+    /// A `HashSet` of the 64-bit codes of the boards (see `PackedBoard::code`), half the size of the boards
+    /// of the 15-puzzle. Only possible for widths up to 4.
+    Packed,
+    /// A bitset with one bit per board that can reach the goal, indexed by `Board::solvable_rank`.
+    /// Only possible for small boards (23 KB for the 8-puzzle, but 1.3 TB for the 15-puzzle),
+    /// the costs and parents are keyed by the codes of the boards, as with `Packed`.
+    Bitset,
+}
+
+impl ClosedList {
+    /// Largest number of bits of a bitset closed list (16 MiB).
+    const MAX_BITSET_SIZE: u128 = 1 << 27;
+
+    /// The most compact closed list that is possible for boards of width `N`.
+    pub fn suited_to<const N: usize>() -> ClosedList {
+        if ClosedList::Bitset.is_possible::<N>() {
+            ClosedList::Bitset
+        } else if ClosedList::Packed.is_possible::<N>() {
+            ClosedList::Packed
+        } else {
+            ClosedList::Hashed
+        }
+    }
+
+    /// Returns `false` if this closed list would be too large for boards of width `N`.
+    pub fn is_possible<const N: usize>(&self) -> bool {
+        match self {
+            ClosedList::Hashed => true,
+            ClosedList::Packed => PackedBoard::<N>::fits(),
+            ClosedList::Bitset => Board::<N>::checked_num_permutations()
+                .is_some_and(|count| count / 2 <= Self::MAX_BITSET_SIZE),
+        }
+    }
+}
+
+/// The set of expanded states, in one of the `ClosedList` representations.
+enum Closed<const N: usize> {
+    Hashed(HashSet<Board<N>>),
+    Packed(HashSet<u64>),
+    Bitset(BitSet),
+}
+
+impl<const N: usize> Closed<N> {
+    fn new(kind: ClosedList) -> Closed<N> {
+        assert!(kind.is_possible::<N>(), "Closed list too large");
+        match kind {
+            ClosedList::Hashed => Closed::Hashed(HashSet::new()),
+            ClosedList::Packed => Closed::Packed(HashSet::new()),
+            ClosedList::Bitset => {
+                Closed::Bitset(BitSet::new((Board::<N>::num_permutations() / 2) as usize))
+            }
+        }
+    }
+
+    fn contains(&self, board: &Board<N>) -> bool {
+        match self {
+            Closed::Hashed(set) => set.contains(board),
+            Closed::Packed(set) => set.contains(&PackedBoard::encode(board)),
+            Closed::Bitset(set) => set.contains(board.solvable_rank() as usize),
+        }
+    }
+
+    fn insert(&mut self, board: Board<N>) -> bool {
+        match self {
+            Closed::Hashed(set) => set.insert(board),
+            Closed::Packed(set) => set.insert(PackedBoard::encode(&board)),
+            Closed::Bitset(set) => set.insert(board.solvable_rank() as usize),
+        }
+    }
+
+    fn remove(&mut self, board: &Board<N>) -> bool {
+        match self {
+            Closed::Hashed(set) => set.remove(board),
+            Closed::Packed(set) => set.remove(&PackedBoard::encode(board)),
+            Closed::Bitset(set) => set.remove(board.solvable_rank() as usize),
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Closed::Hashed(set) => set.len(),
+            Closed::Packed(set) => set.len(),
+            Closed::Bitset(set) => set.len(),
+        }
+    }
+}
+
+/// The costs or the parents of the states of a search, keyed by boards or by their 64-bit codes (see `ClosedList`).
+enum StateMap<const N: usize, V> {
+    Hashed(HashMap<Board<N>, V>),
+    Packed(HashMap<u64, V>),
+}
+
+impl<const N: usize, V> StateMap<N, V> {
+    fn new(kind: ClosedList) -> StateMap<N, V> {
+        match kind {
+            ClosedList::Packed | ClosedList::Bitset if PackedBoard::<N>::fits() => {
+                StateMap::Packed(HashMap::new())
+            }
+            _ => StateMap::Hashed(HashMap::new()),
+        }
+    }
+
+    fn get(&self, board: &Board<N>) -> Option<&V> {
+        match self {
+            StateMap::Hashed(map) => map.get(board),
+            StateMap::Packed(map) => map.get(&PackedBoard::encode(board)),
+        }
+    }
+
+    fn insert(&mut self, board: Board<N>, value: V) {
+        match self {
+            StateMap::Hashed(map) => map.insert(board, value),
+            StateMap::Packed(map) => map.insert(PackedBoard::encode(&board), value),
+        };
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            StateMap::Hashed(map) => map.len(),
+            StateMap::Packed(map) => map.len(),
+        }
+    }
+}
+
+impl<const N: usize> StateMap<N, Direction> {
+    /// Rebuilds the plan leading to `state`, when the map holds the move that led to each state: the parents
+    /// are found by undoing the moves back to the initial state, i.e., the only state without a move.
+    fn plan_to(&self, state: Board<N>) -> Vec<Direction> {
+        let mut directions = Vec::new();
+        match self {
+            StateMap::Hashed(map) => {
+                let mut state = state;
+                while let Some(&action) = map.get(&state) {
+                    directions.push(action);
+                    state = state.apply(action.opposite()).expect("Invalid move");
+                }
+            }
+            StateMap::Packed(map) => {
+                let mut state = PackedBoard::from(state);
+                while let Some(&action) = map.get(&state.code()) {
+                    directions.push(action);
+                    state = state.apply(action.opposite()).expect("Invalid move");
                 }
             }
         }
+        directions.reverse();
+        directions
+    }
+}
 
+/// Rebuilds the plan leading to `state` by following the `parent_action` links back to the initial state,
+/// i.e., the only state without a parent.
+fn extract_plan<const N: usize>(
+    parent_action: &HashMap<Board<N>, (Board<N>, Direction)>,
+    mut state: Board<N>,
+) -> Vec<Direction> {
+    let mut directions = Vec::new();
+    while let Some(&(parent, action)) = parent_action.get(&state) {
+        directions.push(action);
+        state = parent;
+    }
+    directions.reverse();
+    directions
+}
+
+/// A* search from `init_state` to `goal`, guided by the given heuristic.
+///
+/// The returned plan is optimal as long as the heuristic is admissible.
+/// The closed list is the most compact one for the width of the board (see `ClosedList::suited_to`).
+pub fn search<const N: usize>(
+    init_state: Board<N>,
+    goal: &Board<N>,
+    heuristic: &Heuristic,
+) -> (SearchOutcome, Stats) {
+    search_with_closed_list(init_state, goal, heuristic, ClosedList::suited_to::<N>())
+}
+
+/// A* search (see `search`) that stops as soon as one of the `limits` is reached.
+pub fn search_with_limits<const N: usize>(
+    init_state: Board<N>,
+    goal: &Board<N>,
+    heuristic: &Heuristic,
+    limits: &SearchLimits,
+) -> (SearchOutcome, Stats) {
+    weighted_search_with_limits(init_state, goal, heuristic, 1.0, limits)
+}
+
+/// A* search (see `search`) with the given representation of the closed list.
+///
+/// Panics if the closed list is not possible for boards of width `N`.
+pub fn search_with_closed_list<const N: usize>(
+    init_state: Board<N>,
+    goal: &Board<N>,
+    heuristic: &Heuristic,
+    closed_list: ClosedList,
+) -> (SearchOutcome, Stats) {
+    weighted_search_with_closed_list(
+        init_state,
+        goal,
+        heuristic,
+        1.0,
+        closed_list,
+        &SearchLimits::default(),
+    )
+}
+
+/// Weighted A* search from `init_state` to `goal`: the states are expanded by increasing `g + weight * h`.
+///
+/// With a weight larger than 1, the search is greedier: it usually expands far fewer states, but the plan
+/// may be longer than the optimal one, by a factor of at most `weight` if the heuristic is admissible.
+/// The bound actually achieved, i.e., the cost of the plan divided by the smallest f-value `g + h` left in the open list
+/// (a lower bound on the optimal cost), is reported in `Stats::suboptimality_bound`.
+///
+/// Panics if the weight is lower than 1 or larger than `MAX_WEIGHT`.
+pub fn weighted_search<const N: usize>(
+    init_state: Board<N>,
+    goal: &Board<N>,
+    heuristic: &Heuristic,
+    weight: f64,
+) -> (SearchOutcome, Stats) {
+    weighted_search_with_limits(
+        init_state,
+        goal,
+        heuristic,
+        weight,
+        &SearchLimits::default(),
+    )
+}
+
+/// Weighted A* search (see `weighted_search`) that stops as soon as one of the `limits` is reached.
+pub fn weighted_search_with_limits<const N: usize>(
+    init_state: Board<N>,
+    goal: &Board<N>,
+    heuristic: &Heuristic,
+    weight: f64,
+    limits: &SearchLimits,
+) -> (SearchOutcome, Stats) {
+    weighted_search_with_closed_list(
+        init_state,
+        goal,
+        heuristic,
+        weight,
+        ClosedList::suited_to::<N>(),
+        limits,
+    )
+}
+
+/// Precision of the weights in the priorities of weighted A*, which are fixed-point numbers.
+const WEIGHT_SCALE: u32 = 1000;
+
+/// Largest weight accepted by weighted A* and focal search, so that the priorities `g * WEIGHT_SCALE + weight * h`
+/// fit in a `u32` (already much greedier than any useful weight).
+pub const MAX_WEIGHT: f64 = 1000.0;
+
+/// Priority `g + weight * h` of a state in weighted A* and in the focal list, with the weight scaled by `WEIGHT_SCALE`.
+/// Saturates rather than overflowing on (absurdly) long paths.
+fn weighted_priority(g: u32, h: u32, scaled_weight: u32) -> u32 {
+    g.saturating_mul(WEIGHT_SCALE)
+        .saturating_add(scaled_weight.saturating_mul(h))
+}
+
+fn weighted_search_with_closed_list<const N: usize>(
+    init_state: Board<N>,
+    goal: &Board<N>,
+    heuristic: &Heuristic,
+    weight: f64,
+    closed_list: ClosedList,
+    limits: &SearchLimits,
+) -> (SearchOutcome, Stats) {
+    assert!(
+        (1.0..=MAX_WEIGHT).contains(&weight),
+        "Invalid weight {weight}"
+    );
+    let start = std::time::Instant::now();
+    let mut stats = Stats::default();
+    let goal = Goal::new(*goal);
+    let scaled_weight = (weight * WEIGHT_SCALE as f64).round() as u32;
+    let priority = |g: u32, h: u32| weighted_priority(g, h, scaled_weight);
+
+    // the goal is not reachable, no need to explore the (large) connected component of the initial state
+    if !init_state.is_solvable_towards(goal.board()) {
+        stats.runtime = start.elapsed();
+        return (SearchOutcome::Unsolvable, stats);
+    }
+
+    // MinHeap provide allows to store the states to explore, with associated priority
+    let mut heap: MinHeap<Board<N>> = MinHeap::new();
+    // the cost of the best known path to each state, and the estimate of the state
+    let mut costs: StateMap<N, (u32, u32)> = StateMap::new(closed_list);
+    // the move leading to each state on its best known path, from which the parent can be recovered
+    let mut last_move: StateMap<N, Direction> = StateMap::new(closed_list);
+
+    let mut expanded: Closed<N> = Closed::new(closed_list);
+
+    let init_h = heuristic.estimate_towards(&init_state, &goal);
+    costs.insert(init_state, (0, init_h));
+    heap.insert(init_state, priority(0, init_h));
+    stats.record_sizes(heap.len(), expanded.len());
+
+    while let Some(s) = heap.pop() {
+        if expanded.contains(&s) {
+            continue;
+        }
+
+        if s == *goal.board() {
+            // the plan is complete, no need to expand the rest of the search space
+            let plan = last_move.plan_to(s);
+            let cost = plan.len() as u32;
+            // every optimal plan goes through a state of the open list, so the optimal cost is at least its smallest f-value
+            let lower_bound = heap
+                .states()
+                .filter(|state| !expanded.contains(state))
+                .map(|state| {
+                    let (g, h) = costs.get(state).expect("Cannot find the cost");
+                    g + h
+                })
+                .fold(cost, u32::min);
+            stats.suboptimality_bound = Some(match lower_bound {
+                0 => 1.0,
+                _ => cost as f64 / lower_bound as f64,
+            });
+            stats.solution_cost = Some(cost);
+            stats.runtime = start.elapsed();
+            return (SearchOutcome::Solved(plan), stats);
+        }
+
+        if let Some(outcome) = limits.stop::<N>(&stats, costs.len()) {
+            stats.runtime = start.elapsed();
+            return (outcome, stats);
+        }
+
+        let (cost, h) = *costs.get(&s).expect("Cannot find the cost");
+        stats.record_expansion(cost + h);
+        let current_cost = cost + 1;
+        let blank = s.position(EMPTY_CELL);
         for action in DIRECTIONS {
             let sbis = match s.apply(action) {
                 Some(board) => board,
                 None => continue,
             };
-
-            let current_cost = costs.get(&s).expect("Cannot find the cost") + 1;
+            stats.generated += 1;
 
             let found_better_path = match costs.get(&sbis) {
-                Some(previous_cost) => current_cost < *previous_cost,
+                Some((previous_cost, _)) => {
+                    stats.duplicates += 1;
+                    current_cost < *previous_cost
+                }
                 None => true,
             };
 
             if found_better_path {
-                costs.insert(sbis, current_cost);
-                parent_action.insert(sbis, (s, action));
-                heap.insert(sbis, current_cost+ heuristic.estimate(&sbis));
+                // a cheaper path to an already expanded state: it must be expanded again
+                if expanded.remove(&sbis) {
+                    stats.reopened += 1;
+                }
+                // the successors' estimates are updated from the one of `s`, instead of being computed from scratch
+                let sbis_h = heuristic.estimate_after_move(&s, blank, h, action, &sbis, &goal);
+                costs.insert(sbis, (current_cost, sbis_h));
+                last_move.insert(sbis, action);
+                heap.insert(sbis, priority(current_cost, sbis_h));
             }
         }
         expanded.insert(s);
+        stats.record_sizes(heap.len(), expanded.len());
     }
 
-    directions.reverse();
-
-    // here is an example to measure the runtime and returns the statistics
-    let runtime = start.elapsed();
-    // example to construct a Stats instance
-    let stats = Stats::new(0, runtime);
-    // return the results and associated stats
-    (Some(directions), stats)
+    // the whole connected component was explored without meeting the goal
+    stats.runtime = start.elapsed();
+    (SearchOutcome::Unsolvable, stats)
 }
 
 #[cfg(test)]
@@ -111,10 +694,367 @@ mod test {
 
         // validates that search oes return the optimal plan on the first 20 isntances
         for (expected_cost, init) in &INSTANCES[0..20] {
-            let (path, stats) = search(*init, &Heuristic::Blind);
-            let path = path.expect("no plan");
-            assert!(init.is_valid_plan(&path));
+            let (outcome, stats) = search(*init, &Board::GOAL, &Heuristic::Blind);
+            let path = outcome.plan().expect("no plan");
+            assert!(init.is_valid_plan(path));
             assert_eq!(path.len(), *expected_cost as usize);
         }
     }
+
+    #[test]
+    fn test_search_stats() {
+        use super::*;
+
+        let (cost, init) = INSTANCES[12];
+        let (outcome, stats) = search(init, &Board::GOAL, &Heuristic::Manhattan);
+        assert!(outcome.is_solved());
+        assert_eq!(stats.solution_cost, Some(cost));
+        assert!(stats.expanded > 0);
+        assert!(stats.generated >= stats.expanded);
+        assert!(stats.duplicates <= stats.generated);
+        // Manhattan is consistent: no state is ever expanded twice
+        assert_eq!(stats.reopened, 0);
+        assert!(stats.peak_open > 0);
+        assert_eq!(stats.peak_closed, stats.expanded);
+        // with a consistent heuristic the f-layers are increasing and end at the optimal cost
+        assert_eq!(
+            stats.layers.iter().map(|l| l.expanded).sum::<usize>(),
+            stats.expanded
+        );
+        assert!(stats.layers.windows(2).all(|w| w[0].f < w[1].f));
+        assert!(stats.layers.last().unwrap().f <= cost);
+        let b = stats.effective_branching_factor().unwrap();
+        assert!(b > 1.0 && b < 3.0);
+        assert!(stats
+            .to_string()
+            .starts_with(&format!("expanded={} ", stats.expanded)));
+        assert!(stats.to_string().contains(&format!(" cost={cost} ")));
+    }
+
+    #[test]
+    fn test_closed_lists() {
+        use super::*;
+
+        assert_eq!(ClosedList::suited_to::<3>(), ClosedList::Bitset);
+        assert_eq!(ClosedList::suited_to::<4>(), ClosedList::Packed);
+        assert_eq!(ClosedList::suited_to::<5>(), ClosedList::Hashed);
+        // (36)! does not even fit in a u128
+        assert_eq!(ClosedList::suited_to::<6>(), ClosedList::Hashed);
+        assert!(!ClosedList::Bitset.is_possible::<7>());
+        assert!(!ClosedList::Packed.is_possible::<5>());
+        for (cost, init) in &INSTANCES[..20] {
+            let (hashed, hashed_stats) = search_with_closed_list(
+                *init,
+                &Board::GOAL,
+                &Heuristic::Manhattan,
+                ClosedList::Hashed,
+            );
+            let (bitset, bitset_stats) = search_with_closed_list(
+                *init,
+                &Board::GOAL,
+                &Heuristic::Manhattan,
+                ClosedList::Bitset,
+            );
+            let (packed, packed_stats) = search_with_closed_list(
+                *init,
+                &Board::GOAL,
+                &Heuristic::Manhattan,
+                ClosedList::Packed,
+            );
+            assert_eq!(hashed, bitset);
+            assert_eq!(hashed, packed);
+            assert_eq!(bitset.plan().map(|p| p.len()), Some(*cost as usize));
+            assert_eq!(hashed_stats.expanded, bitset_stats.expanded);
+            assert_eq!(hashed_stats.peak_closed, bitset_stats.peak_closed);
+            assert_eq!(hashed_stats.expanded, packed_stats.expanded);
+            assert_eq!(hashed_stats.peak_closed, packed_stats.peak_closed);
+        }
+        // the packed closed list and maps of the 15-puzzle, against the ones keyed by boards
+        let mut generator = crate::generator::Generator::new(5);
+        let heuristic = Heuristic::Manhattan;
+        for _ in 0..5 {
+            let init: Board15 = generator.random_walk(30);
+            let (hashed, hashed_stats) =
+                search_with_closed_list(init, &Board::GOAL, &heuristic, ClosedList::Hashed);
+            let (packed, packed_stats) =
+                search_with_closed_list(init, &Board::GOAL, &heuristic, ClosedList::Packed);
+            assert!(init.is_valid_plan(packed.plan().expect("no plan")));
+            assert_eq!(hashed, packed);
+            assert_eq!(hashed_stats.expanded, packed_stats.expanded);
+            assert_eq!(hashed_stats.peak_closed, packed_stats.peak_closed);
+        }
+        // the bitset also works towards the goals of the other parity
+        let goal = Board::new([[2, 1, 3], [4, 5, 6], [7, 8, 0]]);
+        let init = Board::new([[2, 1, 3], [4, 5, 0], [7, 8, 6]]);
+        let (outcome, _) =
+            search_with_closed_list(init, &goal, &Heuristic::Manhattan, ClosedList::Bitset);
+        assert_eq!(outcome, SearchOutcome::Solved(vec![Direction::Down]));
+    }
+
+    #[test]
+    fn test_weighted_search() {
+        use super::*;
+
+        let mut total_expanded = [0; 3];
+        for (cost, init) in &INSTANCES[..24] {
+            for (i, weight) in [1.0, 1.5, 3.0].into_iter().enumerate() {
+                let (outcome, stats) =
+                    weighted_search(*init, &Board::GOAL, &Heuristic::Manhattan, weight);
+                let plan = outcome.plan().expect("no plan");
+                assert!(init.is_valid_plan(plan));
+                assert!(plan.len() as f64 <= weight * *cost as f64);
+                let bound = stats.suboptimality_bound.unwrap();
+                assert!((1.0..=weight).contains(&bound));
+                assert!(plan.len() as f64 <= bound * *cost as f64 + 1e-9);
+                total_expanded[i] += stats.expanded;
+            }
+        }
+        assert!(total_expanded[1] < total_expanded[0]);
+        assert!(total_expanded[2] < total_expanded[0]);
+
+        // a 15-puzzle instance far from the goal
+        let board: Board15 = crate::generator::Generator::new(19).random_walk(60);
+        let (outcome, stats) =
+            weighted_search(board, &Board::GOAL, &Heuristic::LinearConflict, 2.0);
+        assert!(board.is_valid_plan(outcome.plan().expect("no plan")));
+        assert!(stats.suboptimality_bound.unwrap() <= 2.0);
+        assert!(stats.to_string().contains(" bound="));
+
+        // the largest weight does not overflow the priorities, even on the 24-puzzle
+        let (_, init) = INSTANCES[INSTANCES.len() - 1];
+        let (outcome, stats) =
+            weighted_search(init, &Board::GOAL, &Heuristic::Manhattan, MAX_WEIGHT);
+        assert!(init.is_valid_plan(outcome.plan().expect("no plan")));
+        assert!(stats.suboptimality_bound.unwrap() <= MAX_WEIGHT);
+        let board: Board24 = crate::generator::Generator::new(3).random_walk(100);
+        let (outcome, _) = weighted_search(board, &Board::GOAL, &Heuristic::Manhattan, MAX_WEIGHT);
+        assert!(board.is_valid_plan(outcome.plan().expect("no plan")));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid weight")]
+    fn test_weighted_search_invalid_weight() {
+        use super::*;
+
+        weighted_search(Board8::GOAL, &Board::GOAL, &Heuristic::Manhattan, 1e7);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid weight")]
+    fn test_weighted_search_weight_below_one() {
+        use super::*;
+
+        // a weight lower than 1 would not give any bound on the cost of the plan
+        weighted_search(Board8::GOAL, &Board::GOAL, &Heuristic::Manhattan, 0.5);
+    }
+
+    #[test]
+    fn test_search_limits() {
+        use super::*;
+
+        let (cost, init) = INSTANCES[INSTANCES.len() - 1];
+        let heuristic = Heuristic::Manhattan;
+        let stopped = |outcome: SearchOutcome, stats: &Stats, limit: Limit| {
+            assert_eq!(outcome, SearchOutcome::ExhaustedLimit { limit });
+            assert_eq!(stats.solution_cost, None);
+        };
+
+        // every algorithm stops after the same number of expansions
+        let limits = SearchLimits {
+            max_expanded: Some(500),
+            ..SearchLimits::default()
+        };
+        let runs = [
+            search_with_limits(init, &Board::GOAL, &heuristic, &limits),
+            weighted_search_with_limits(init, &Board::GOAL, &heuristic, 1.5, &limits),
+            focal_search_with_limits(init, &Board::GOAL, &heuristic, 1.2, &limits),
+            bidirectional_search_with_limits(init, &Board::GOAL, &heuristic, &limits),
+            ida_star_with_limits(init, &Board::GOAL, &heuristic, &limits),
+            breadth_first_search_with_limits(init, &Board::GOAL, &limits),
+            dijkstra_with_limits(init, &Board::GOAL, &limits),
+            iterative_deepening_search_with_limits(init, &Board::GOAL, true, &limits),
+            depth_limited_search_with_limits(init, &Board::GOAL, cost, false, &limits),
+        ];
+        for (outcome, stats) in runs {
+            stopped(outcome, &stats, Limit::Expanded);
+            assert_eq!(stats.expanded, 500);
+            // the partial statistics are still consistent
+            assert_eq!(stats.layers.iter().map(|l| l.expanded).sum::<usize>(), 500);
+        }
+        // greedy search needs fewer expansions
+        let limits = SearchLimits {
+            max_expanded: Some(10),
+            ..SearchLimits::default()
+        };
+        let (outcome, stats) = greedy_search_with_limits(init, &Board::GOAL, &heuristic, &limits);
+        stopped(outcome, &stats, Limit::Expanded);
+
+        // the expanded states are among the stored ones
+        let limits = SearchLimits {
+            max_stored: Some(1000),
+            ..SearchLimits::default()
+        };
+        let (outcome, stats) = search_with_limits(init, &Board::GOAL, &heuristic, &limits);
+        stopped(outcome, &stats, Limit::Stored);
+        assert!(stats.peak_closed > 0 && stats.peak_closed <= 1000);
+
+        let limits = SearchLimits {
+            max_bytes: Some(SearchLimits::approximate_bytes::<3>(1000)),
+            ..SearchLimits::default()
+        };
+        let (outcome, _) = dijkstra_with_limits(init, &Board::GOAL, &limits);
+        assert_eq!(
+            outcome,
+            SearchOutcome::ExhaustedLimit {
+                limit: Limit::Memory
+            }
+        );
+
+        // the deadline is checked before the first expansion
+        let limits = SearchLimits::default().with_timeout(Duration::ZERO);
+        let (outcome, stats) = ida_star_with_limits(init, &Board::GOAL, &heuristic, &limits);
+        stopped(outcome, &stats, Limit::Deadline);
+        assert_eq!(stats.expanded, 0);
+
+        // limits that are not reached do not change the search
+        let limits = SearchLimits {
+            max_expanded: Some(1_000_000),
+            max_stored: Some(1_000_000),
+            ..SearchLimits::default()
+        }
+        .with_timeout(Duration::from_secs(60));
+        let (outcome, stats) = search_with_limits(init, &Board::GOAL, &heuristic, &limits);
+        let (_, unlimited) = search(init, &Board::GOAL, &heuristic);
+        assert_eq!(outcome.plan().map(|p| p.len()), Some(cost as usize));
+        assert_eq!(stats.expanded, unlimited.expanded);
+    }
+
+    #[test]
+    fn test_cancellation() {
+        use super::*;
+
+        // an already cancelled search stops before its first expansion
+        let (_, init) = INSTANCES[INSTANCES.len() - 1];
+        let token = CancellationToken::new();
+        token.cancel();
+        let limits = SearchLimits::default().with_cancellation(token);
+        let heuristic = Heuristic::Manhattan;
+        let runs = [
+            search_with_limits(init, &Board::GOAL, &heuristic, &limits),
+            focal_search_with_limits(init, &Board::GOAL, &heuristic, 1.2, &limits),
+            greedy_search_with_limits(init, &Board::GOAL, &heuristic, &limits),
+            bidirectional_search_with_limits(init, &Board::GOAL, &heuristic, &limits),
+            ida_star_with_limits(init, &Board::GOAL, &heuristic, &limits),
+            breadth_first_search_with_limits(init, &Board::GOAL, &limits),
+            dijkstra_with_limits(init, &Board::GOAL, &limits),
+            iterative_deepening_search_with_limits(init, &Board::GOAL, false, &limits),
+        ];
+        for (outcome, stats) in runs {
+            assert_eq!(outcome, SearchOutcome::Cancelled);
+            assert_eq!(stats.expanded, 0);
+        }
+
+        // A* and IDA* on a hard 15-puzzle instance (57 moves from the goal with the empty cell first),
+        // cancelled from another thread
+        let goal = Board15::blank_first_goal();
+        let init = Board15::new([[14, 13, 15, 7], [11, 12, 9, 5], [6, 0, 2, 1], [4, 8, 10, 3]]);
+        for algorithm in [search_with_limits::<4>, ida_star_with_limits::<4>] {
+            let token = CancellationToken::new();
+            let limits = SearchLimits::default().with_cancellation(token.clone());
+            let (outcome, stats) = std::thread::scope(|scope| {
+                let search = scope.spawn(|| algorithm(init, &goal, &Heuristic::Manhattan, &limits));
+                std::thread::sleep(Duration::from_millis(20));
+                token.cancel();
+                search.join().unwrap()
+            });
+            assert_eq!(outcome, SearchOutcome::Cancelled);
+            assert!(token.is_cancelled());
+            // the partial statistics of the search so far
+            assert!(stats.expanded > 0);
+            assert!(stats.generated >= stats.expanded);
+            assert!(!stats.layers.is_empty());
+            assert_eq!(stats.solution_cost, None);
+        }
+    }
+
+    #[test]
+    fn test_effective_branching_factor() {
+        use super::*;
+
+        let mut stats = Stats::new(0, Duration::ZERO);
+        assert_eq!(stats.effective_branching_factor(), None);
+        // 2 + 4 + 8 = 14
+        stats.generated = 14;
+        stats.solution_cost = Some(3);
+        assert!((stats.effective_branching_factor().unwrap() - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_search_goal() {
+        use super::*;
+
+        // the initial state is the goal: the plan is empty but the search does succeed
+        let (outcome, _) = search(Board8::GOAL, &Board::GOAL, &Heuristic::Manhattan);
+        assert_eq!(outcome, SearchOutcome::Solved(vec![]));
+        assert!(outcome.is_solved());
+    }
+
+    #[test]
+    fn test_search_other_goals() {
+        use super::*;
+
+        let goals: [Board<3>; 2] = [Board::blank_first_goal(), Board::spiral_goal()];
+        for goal in goals {
+            for (_, init) in &INSTANCES[0..12] {
+                let (outcome, _) = search(*init, &goal, &Heuristic::LinearConflict);
+                if !init.is_solvable_towards(&goal) {
+                    assert_eq!(outcome, SearchOutcome::Unsolvable);
+                    continue;
+                }
+                let plan = outcome.plan().expect("no plan");
+                assert!(init.validate_plan_towards(plan, &goal).is_valid());
+                // Manhattan is admissible and consistent as well: both plans are optimal
+                let (optimal, _) = search(*init, &goal, &Heuristic::Manhattan);
+                assert_eq!(plan.len(), optimal.plan().unwrap().len());
+            }
+        }
+
+        let goal = Board15::spiral_goal();
+        let init = goal
+            .apply(Direction::Up)
+            .unwrap()
+            .apply(Direction::Left)
+            .unwrap();
+        let (outcome, _) = search(init, &goal, &Heuristic::Manhattan);
+        assert_eq!(
+            outcome,
+            SearchOutcome::Solved(vec![Direction::Right, Direction::Down])
+        );
+    }
+
+    #[test]
+    fn test_search_unsolvable() {
+        use super::*;
+
+        let init = Board::new([[2, 1, 3], [4, 5, 6], [7, 8, 0]]);
+        let (outcome, stats) = search(init, &Board::GOAL, &Heuristic::Manhattan);
+        assert_eq!(outcome, SearchOutcome::Unsolvable);
+        assert_eq!(stats.expanded, 0);
+    }
+
+    #[test]
+    fn test_search_larger_boards() {
+        use super::*;
+        use Direction::*;
+
+        let init = Board::new([[1, 2, 3, 4], [5, 6, 0, 8], [9, 10, 7, 11], [13, 14, 15, 12]]);
+        let (outcome, _) = search(init, &Board::GOAL, &Heuristic::Manhattan);
+        let path = outcome.plan().expect("no plan");
+        assert!(init.is_valid_plan(path));
+        assert_eq!(path.len(), 3);
+
+        let init = Board24::GOAL.apply(Up).unwrap().apply(Left).unwrap();
+        let (outcome, _) = search(init, &Board::GOAL, &Heuristic::Manhattan);
+        assert_eq!(outcome, SearchOutcome::Solved(vec![Right, Down]));
+    }
 }
@@ -0,0 +1,276 @@
+use crate::board::*;
+use crate::goal::*;
+use crate::heuristics::*;
+use crate::search::*;
+use std::collections::BTreeSet;
+
+/// One of the two searches of a bidirectional search: forward from the initial state, or backward from the goal.
+struct Frontier<'a, const N: usize> {
+    /// The heuristic towards the other end of the search.
+    heuristic: &'a Heuristic,
+    /// The other end of the search.
+    target: Goal<N>,
+    /// The cost of the best known path from the origin of the search to each state, and its estimate.
+    costs: HashMap<Board<N>, (u32, u32)>,
+    /// The move that reached each state from its parent, on the best known path from the origin.
+    parent_action: HashMap<Board<N>, (Board<N>, Direction)>,
+    /// The open states by increasing priority `max(g + h, 2 * g + 1)`, then cost.
+    by_priority: BTreeSet<(u32, u32, Board<N>)>,
+    /// The open states by increasing f-value.
+    by_f: BTreeSet<(u32, Board<N>)>,
+    /// The open states by increasing cost.
+    by_g: BTreeSet<(u32, Board<N>)>,
+    expanded: HashSet<Board<N>>,
+}
+
+impl<'a, const N: usize> Frontier<'a, N> {
+    fn new(origin: Board<N>, target: Board<N>, heuristic: &'a Heuristic) -> Frontier<'a, N> {
+        let target = Goal::new(target);
+        let mut frontier = Frontier {
+            heuristic,
+            costs: HashMap::new(),
+            parent_action: HashMap::new(),
+            by_priority: BTreeSet::new(),
+            by_f: BTreeSet::new(),
+            by_g: BTreeSet::new(),
+            expanded: HashSet::new(),
+            target,
+        };
+        let h = heuristic.estimate_towards(&origin, &frontier.target);
+        frontier.open(origin, 0, h);
+        frontier
+    }
+
+    /// Priority of an open state in MM: it is expanded no sooner than its f-value, nor before the searches
+    /// may have met halfway through its path (the `+ 1` is the cost of the cheapest move).
+    fn priority(g: u32, h: u32) -> u32 {
+        (g + h).max(2 * g + 1)
+    }
+
+    fn open(&mut self, board: Board<N>, g: u32, h: u32) {
+        self.costs.insert(board, (g, h));
+        self.by_priority.insert((Self::priority(g, h), g, board));
+        self.by_f.insert((g + h, board));
+        self.by_g.insert((g, board));
+    }
+
+    /// Removes a state from the open states, or from the expanded states.
+    /// Returns `true` if the state had been expanded.
+    fn remove(&mut self, board: Board<N>) -> bool {
+        if self.expanded.remove(&board) {
+            return true;
+        }
+        let (g, h) = self.costs[&board];
+        self.by_priority.remove(&(Self::priority(g, h), g, board));
+        self.by_f.remove(&(g + h, board));
+        self.by_g.remove(&(g, board));
+        false
+    }
+
+    fn is_open(&self, board: &Board<N>) -> bool {
+        self.costs.contains_key(board) && !self.expanded.contains(board)
+    }
+
+    /// Smallest priority, f-value and cost of the open states.
+    fn min_priority(&self) -> u32 {
+        self.by_priority.first().map_or(u32::MAX, |&(p, _, _)| p)
+    }
+
+    fn min_f(&self) -> u32 {
+        self.by_f.first().map_or(u32::MAX, |&(f, _)| f)
+    }
+
+    fn min_g(&self) -> u32 {
+        self.by_g.first().map_or(u32::MAX, |&(g, _)| g)
+    }
+}
+
+/// Bidirectional search from `init_state` to `goal`, that searches forward from the initial state and backward
+/// from the goal until the two searches meet in the middle. It follows MM (Holte et al., 2016): each search is guided
+/// by the heuristic towards its own end (front-to-end), and expands its states by increasing `max(f, 2 * g + 1)`,
+/// so that neither search goes beyond the middle of the optimal plan.
+///
+/// Each time a state is reached by both searches, the sum of its two costs is a plan; the best one is returned
+/// once it is proven optimal, i.e., once no cheaper plan could be found from the open states (assuming
+/// the heuristic is admissible). The backward half of the plan is reversed with `Direction::opposite`.
+///
+/// The backward search needs a heuristic towards the initial state: pattern databases and distance tables
+/// are built again towards it (see `Heuristic::for_goal`).
+pub fn bidirectional_search<const N: usize>(
+    init_state: Board<N>,
+    goal: &Board<N>,
+    heuristic: &Heuristic,
+) -> (SearchOutcome, Stats) {
+    bidirectional_search_with_limits(init_state, goal, heuristic, &SearchLimits::default())
+}
+
+/// Bidirectional search (see `bidirectional_search`) that stops as soon as one of the `limits` is reached,
+/// the states of both searches being counted together.
+pub fn bidirectional_search_with_limits<const N: usize>(
+    init_state: Board<N>,
+    goal: &Board<N>,
+    heuristic: &Heuristic,
+    limits: &SearchLimits,
+) -> (SearchOutcome, Stats) {
+    let start = std::time::Instant::now();
+    let mut stats = Stats::default();
+
+    if !init_state.is_solvable_towards(goal) {
+        stats.runtime = start.elapsed();
+        return (SearchOutcome::Unsolvable, stats);
+    }
+
+    let backward_heuristic = heuristic.for_goal(&init_state);
+    let mut forward = Frontier::new(init_state, *goal, heuristic);
+    let mut backward = Frontier::new(*goal, init_state, &backward_heuristic);
+    stats.record_sizes(2, 0);
+
+    // the cost of the best plan found so far, and the state where the two searches met on this plan
+    let mut best: Option<(u32, Board<N>)> = (init_state == *goal).then_some((0, init_state));
+    loop {
+        let min_priority = forward.min_priority().min(backward.min_priority());
+        // lower bound on the cost of any plan that has not been found yet
+        let lower_bound = min_priority.max(forward.min_f()).max(backward.min_f()).max(
+            forward
+                .min_g()
+                .saturating_add(backward.min_g())
+                .saturating_add(1),
+        );
+        if let Some((cost, middle)) = best {
+            if cost <= lower_bound {
+                let mut plan = extract_plan(&forward.parent_action, middle);
+                // the backward search reached each state from a state closer to the goal, by the opposite move
+                let mut state = middle;
+                while let Some(&(parent, action)) = backward.parent_action.get(&state) {
+                    plan.push(action.opposite());
+                    state = parent;
+                }
+                stats.solution_cost = Some(plan.len() as u32);
+                stats.runtime = start.elapsed();
+                return (SearchOutcome::Solved(plan), stats);
+            }
+        }
+        if min_priority == u32::MAX {
+            // both open lists are empty
+            break;
+        }
+        let stored = forward.costs.len() + backward.costs.len();
+        if let Some(outcome) = limits.stop::<N>(&stats, stored) {
+            stats.runtime = start.elapsed();
+            return (outcome, stats);
+        }
+
+        // expand the search with the smallest priority
+        let (frontier, other) = if forward.min_priority() <= backward.min_priority() {
+            (&mut forward, &mut backward)
+        } else {
+            (&mut backward, &mut forward)
+        };
+        let &(_, _, s) = frontier.by_priority.first().unwrap();
+        let (g, h) = frontier.costs[&s];
+        frontier.remove(s);
+        frontier.expanded.insert(s);
+        stats.record_expansion(min_priority);
+
+        let blank = s.position(EMPTY_CELL);
+        for action in DIRECTIONS {
+            let Some(successor) = s.apply(action) else {
+                continue;
+            };
+            stats.generated += 1;
+            if let Some(&(previous_g, _)) = frontier.costs.get(&successor) {
+                stats.duplicates += 1;
+                if previous_g <= g + 1 {
+                    continue;
+                }
+                // a cheaper path to a known state: it must be (re)expanded with its new cost
+                if frontier.remove(successor) {
+                    stats.reopened += 1;
+                }
+            }
+            let successor_h = frontier.heuristic.estimate_after_move(
+                &s,
+                blank,
+                h,
+                action,
+                &successor,
+                &frontier.target,
+            );
+            frontier.open(successor, g + 1, successor_h);
+            frontier.parent_action.insert(successor, (s, action));
+
+            // the two searches meet
+            if other.is_open(&successor) {
+                let cost = g + 1 + other.costs[&successor].0;
+                if best.is_none_or(|(best_cost, _)| cost < best_cost) {
+                    best = Some((cost, successor));
+                }
+            }
+        }
+        stats.record_sizes(
+            forward.by_g.len() + backward.by_g.len(),
+            forward.expanded.len() + backward.expanded.len(),
+        );
+    }
+
+    stats.runtime = start.elapsed();
+    (SearchOutcome::Unsolvable, stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::*;
+    use crate::pattern_database::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_bidirectional_search() {
+        for (cost, board) in &INSTANCES {
+            let (outcome, stats) =
+                bidirectional_search(*board, &Board::GOAL, &Heuristic::Manhattan);
+            let plan = outcome.plan().expect("no plan");
+            assert!(board.is_valid_plan(plan));
+            assert_eq!(plan.len(), *cost as usize);
+            assert_eq!(stats.solution_cost, Some(*cost));
+        }
+
+        // the backward search uses a pattern database built towards the initial state
+        let pdb = PatternDatabase::build::<3>(&[vec![1, 2, 3], vec![4, 5, 6], vec![7, 8]]);
+        let heuristic = Heuristic::PatternDatabase(Arc::new(pdb));
+        for (cost, board) in &INSTANCES[30..] {
+            let (outcome, _) = bidirectional_search(*board, &Board::GOAL, &heuristic);
+            assert_eq!(outcome.plan().map(|p| p.len()), Some(*cost as usize));
+        }
+
+        let (outcome, stats) =
+            bidirectional_search(Board8::GOAL, &Board::GOAL, &Heuristic::Manhattan);
+        assert_eq!(outcome, SearchOutcome::Solved(vec![]));
+        assert_eq!(stats.expanded, 0);
+    }
+
+    #[test]
+    fn test_larger_boards() {
+        let mut generator = Generator::new(8);
+        for goal in [Board15::GOAL, Board15::spiral_goal()] {
+            // a random walk from the goal
+            let mut board = goal;
+            for _ in 0..100 {
+                board = board.apply(DIRECTIONS[generator.below(4)]).unwrap_or(board);
+            }
+            let (expected, _) = ida_star(board, &goal, &Heuristic::LinearConflict);
+            let (outcome, _) = bidirectional_search(board, &goal, &Heuristic::LinearConflict);
+            let plan = outcome.plan().expect("no plan");
+            assert!(board.validate_plan_towards(plan, &goal).is_valid());
+            assert_eq!(plan.len(), expected.plan().unwrap().len());
+            assert!(plan.len() > 20);
+        }
+    }
+
+    #[test]
+    fn test_unsolvable() {
+        let board = Board::new([[2, 1, 3], [4, 5, 6], [7, 8, 0]]);
+        let (outcome, _) = bidirectional_search(board, &Board::GOAL, &Heuristic::Manhattan);
+        assert_eq!(outcome, SearchOutcome::Unsolvable);
+    }
+}
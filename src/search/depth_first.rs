@@ -0,0 +1,270 @@
+use crate::board::*;
+use crate::search::*;
+
+/// State of a depth-first search shared by all recursive calls.
+struct DepthFirst<'a, const N: usize> {
+    goal: &'a Board<N>,
+    /// If `true`, the successors already on the current path are skipped.
+    detect_cycles: bool,
+    /// Moves leading from the initial state to the current one.
+    path: Vec<Direction>,
+    /// States of the current path, from the initial state (only maintained to detect cycles).
+    states: Vec<Board<N>>,
+    /// Whether some states were not explored because they were beyond the depth limit.
+    cutoff: bool,
+    limits: &'a SearchLimits,
+    /// The outcome of the search if it was stopped by one of its limits or cancelled.
+    stopped: Option<SearchOutcome>,
+    stats: Stats,
+}
+
+impl<const N: usize> DepthFirst<'_, N> {
+    /// Depth-first exploration of the states reachable from `board` in at most `depth` moves.
+    /// Returns `true` if the goal was reached, the plan being in the path.
+    fn explore(&mut self, board: &Board<N>, depth: u32, limit: u32) -> bool {
+        if board == self.goal {
+            return true;
+        }
+        if depth == 0 {
+            self.cutoff = true;
+            return false;
+        }
+        if let Some(outcome) = self.limits.stop::<N>(&self.stats, self.path.len() + 1) {
+            self.stopped = Some(outcome);
+            return false;
+        }
+
+        self.stats.record_expansion(limit);
+        self.stats.record_sizes(self.path.len() + 1, 0);
+        for action in DIRECTIONS {
+            // never undo the previous move: it would lead back to the parent state
+            if self.path.last() == Some(&action.opposite()) {
+                continue;
+            }
+            let Some(successor) = board.apply(action) else {
+                continue;
+            };
+            self.stats.generated += 1;
+            if self.detect_cycles && self.states.contains(&successor) {
+                self.stats.duplicates += 1;
+                continue;
+            }
+
+            self.path.push(action);
+            if self.detect_cycles {
+                self.states.push(successor);
+            }
+            if self.explore(&successor, depth - 1, limit) {
+                return true;
+            }
+            if self.stopped.is_some() {
+                return false;
+            }
+            self.path.pop();
+            if self.detect_cycles {
+                self.states.pop();
+            }
+        }
+        false
+    }
+
+    /// Explores the states reachable from `init_state` in at most `limit` moves.
+    fn run(&mut self, init_state: &Board<N>, limit: u32) -> bool {
+        self.path.clear();
+        self.states.clear();
+        self.states.push(*init_state);
+        self.cutoff = false;
+        self.explore(init_state, limit, limit)
+    }
+}
+
+/// Depth-limited depth-first search from `init_state` to `goal`, that only explores the plans of at most `max_depth` moves.
+///
+/// The plan found is the first one met, which is not necessarily the shortest one. If no plan is found but some states
+/// were cut off, the search stops with `Limit::Depth`. The moves that immediately undo the previous one are never
+/// considered, and if `detect_cycles` is set, neither are the moves leading back to a state of the current path.
+/// Only the current path is kept in memory.
+pub fn depth_limited_search<const N: usize>(
+    init_state: Board<N>,
+    goal: &Board<N>,
+    max_depth: u32,
+    detect_cycles: bool,
+) -> (SearchOutcome, Stats) {
+    depth_limited_search_with_limits(
+        init_state,
+        goal,
+        max_depth,
+        detect_cycles,
+        &SearchLimits::default(),
+    )
+}
+
+/// Depth-limited search (see `depth_limited_search`) that also stops as soon as one of the `limits` is reached.
+pub fn depth_limited_search_with_limits<const N: usize>(
+    init_state: Board<N>,
+    goal: &Board<N>,
+    max_depth: u32,
+    detect_cycles: bool,
+    limits: &SearchLimits,
+) -> (SearchOutcome, Stats) {
+    let start = std::time::Instant::now();
+    let mut search = DepthFirst {
+        goal,
+        detect_cycles,
+        path: Vec::new(),
+        states: Vec::new(),
+        cutoff: false,
+        limits,
+        stopped: None,
+        stats: Stats::default(),
+    };
+
+    let outcome = if !init_state.is_solvable_towards(goal) {
+        SearchOutcome::Unsolvable
+    } else if search.run(&init_state, max_depth) {
+        search.stats.solution_cost = Some(search.path.len() as u32);
+        SearchOutcome::Solved(std::mem::take(&mut search.path))
+    } else if let Some(outcome) = search.stopped.take() {
+        outcome
+    } else if search.cutoff {
+        SearchOutcome::ExhaustedLimit {
+            limit: Limit::Depth,
+        }
+    } else {
+        SearchOutcome::Unsolvable
+    };
+    search.stats.runtime = start.elapsed();
+    (outcome, search.stats)
+}
+
+/// Iterative deepening depth-first search (IDDFS) from `init_state` to `goal`: depth-limited searches with
+/// increasing depth limits 0, 1, 2... until a plan is found, which is then optimal.
+///
+/// Each iteration is reported as one of the `Stats::layers`, with the depth limit as its f-value and the number of states
+/// expanded during the iteration: all but the last iteration are re-expansion overhead.
+/// As in `depth_limited_search`, only the current path is kept in memory.
+pub fn iterative_deepening_search<const N: usize>(
+    init_state: Board<N>,
+    goal: &Board<N>,
+    detect_cycles: bool,
+) -> (SearchOutcome, Stats) {
+    iterative_deepening_search_with_limits(
+        init_state,
+        goal,
+        detect_cycles,
+        &SearchLimits::default(),
+    )
+}
+
+/// Iterative deepening search (see `iterative_deepening_search`) that stops as soon as one of the `limits` is reached.
+pub fn iterative_deepening_search_with_limits<const N: usize>(
+    init_state: Board<N>,
+    goal: &Board<N>,
+    detect_cycles: bool,
+    limits: &SearchLimits,
+) -> (SearchOutcome, Stats) {
+    let start = std::time::Instant::now();
+    let mut search = DepthFirst {
+        goal,
+        detect_cycles,
+        path: Vec::new(),
+        states: Vec::new(),
+        cutoff: false,
+        limits,
+        stopped: None,
+        stats: Stats::default(),
+    };
+
+    if !init_state.is_solvable_towards(goal) {
+        search.stats.runtime = start.elapsed();
+        return (SearchOutcome::Unsolvable, search.stats);
+    }
+
+    let mut limit = 0;
+    let outcome = loop {
+        if search.run(&init_state, limit) {
+            search.stats.solution_cost = Some(search.path.len() as u32);
+            break SearchOutcome::Solved(std::mem::take(&mut search.path));
+        }
+        if let Some(outcome) = search.stopped.take() {
+            break outcome;
+        }
+        if !search.cutoff {
+            // the whole (finite) search tree was explored
+            break SearchOutcome::Unsolvable;
+        }
+        limit += 1;
+    };
+    search.stats.runtime = start.elapsed();
+    (outcome, search.stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_depth_limited_search() {
+        let (cost, board) = INSTANCES[8];
+        let (outcome, stats) = depth_limited_search(board, &Board::GOAL, cost + 4, true);
+        let plan = outcome.plan().expect("no plan");
+        assert!(board.is_valid_plan(plan));
+        assert!(plan.len() as u32 <= cost + 4);
+        assert_eq!(stats.layers.len(), 1);
+
+        let (outcome, stats) = depth_limited_search(board, &Board::GOAL, cost - 1, false);
+        assert_eq!(
+            outcome,
+            SearchOutcome::ExhaustedLimit {
+                limit: Limit::Depth
+            }
+        );
+        assert!(stats.expanded > 0);
+        assert_eq!(stats.solution_cost, None);
+        // only the current path is stored
+        assert_eq!(stats.peak_open, (cost - 1) as usize);
+
+        let unsolvable = Board::new([[2, 1, 3], [4, 5, 6], [7, 8, 0]]);
+        let (outcome, _) = depth_limited_search(unsolvable, &Board::GOAL, 10, false);
+        assert_eq!(outcome, SearchOutcome::Unsolvable);
+    }
+
+    #[test]
+    fn test_iterative_deepening_search() {
+        for (cost, board) in &INSTANCES[..12] {
+            let (outcome, stats) = iterative_deepening_search(*board, &Board::GOAL, false);
+            let plan = outcome.plan().expect("no plan");
+            assert!(board.is_valid_plan(plan));
+            assert_eq!(plan.len(), *cost as usize);
+            // one iteration per depth limit, up to the optimal cost (nothing is expanded with a limit of 0)
+            let limits: Vec<u32> = stats.layers.iter().map(|layer| layer.f).collect();
+            assert_eq!(limits, (1..=*cost).collect::<Vec<_>>());
+
+            // skipping the states of the current path prunes the search
+            let (outcome, pruned) = iterative_deepening_search(*board, &Board::GOAL, true);
+            assert_eq!(outcome.plan().map(|p| p.len()), Some(*cost as usize));
+            assert!(pruned.expanded <= stats.expanded);
+        }
+
+        // every complete iteration re-expands the states of the previous one, and then some
+        let (cost, board) = INSTANCES[11];
+        let (_, stats) = iterative_deepening_search(board, &Board::GOAL, true);
+        assert_eq!(stats.layers.len(), cost as usize);
+        let complete = &stats.layers[..stats.layers.len() - 1];
+        for pair in complete.windows(2) {
+            assert!(pair[1].expanded > pair[0].expanded);
+        }
+
+        let goal = Board15::spiral_goal();
+        let board = goal
+            .apply(Direction::Left)
+            .unwrap()
+            .apply(Direction::Up)
+            .unwrap();
+        let (outcome, _) = iterative_deepening_search(board, &goal, false);
+        assert_eq!(
+            outcome.plan(),
+            Some(&[Direction::Down, Direction::Right][..])
+        );
+    }
+}
@@ -0,0 +1,244 @@
+use crate::board::*;
+use crate::goal::*;
+use crate::heuristics::*;
+use crate::search::*;
+use std::collections::BTreeSet;
+
+/// The open and focal lists of a focal search.
+struct Focal<const N: usize> {
+    weight: f64,
+    /// Weight of the estimates in the order of the focal list, as a fixed-point number (see `WEIGHT_SCALE`).
+    scaled_weight: u32,
+    /// The states to expand, by increasing f-value: `(f, h, state)`.
+    open: BTreeSet<(u32, u32, Board<N>)>,
+    /// The states of the open list whose f-value is at most `threshold`, by increasing `g + weight * h`:
+    /// `(priority, f, state)`.
+    focal: BTreeSet<(u32, u32, Board<N>)>,
+    /// Largest f-value of the states in the focal list: `weight` times the smallest f-value of the open list.
+    threshold: u32,
+}
+
+impl<const N: usize> Focal<N> {
+    /// Priority of a state in the focal list, as in weighted A*.
+    fn priority(&self, f: u32, h: u32) -> u32 {
+        weighted_priority(f - h, h, self.scaled_weight)
+    }
+
+    /// Adds a state to the open list, and to the focal list if its f-value is low enough.
+    fn insert(&mut self, board: Board<N>, f: u32, h: u32) {
+        self.open.insert((f, h, board));
+        if f <= self.threshold {
+            self.focal.insert((self.priority(f, h), f, board));
+        }
+    }
+
+    /// Removes a state from the open and focal lists.
+    fn remove(&mut self, board: Board<N>, f: u32, h: u32) {
+        self.open.remove(&(f, h, board));
+        self.focal.remove(&(self.priority(f, h), f, board));
+    }
+
+    /// Updates the focal list after the smallest f-value of the open list has changed.
+    fn update_threshold(&mut self) {
+        let Some(&(f_min, _, _)) = self.open.first() else {
+            return;
+        };
+        let threshold = (self.weight * f_min as f64).floor() as u32;
+        // the smallest board, to start a range of the open list at a given f-value
+        let first = Board::new([[0; N]; N]);
+        let (low, high) = (threshold.min(self.threshold), threshold.max(self.threshold));
+        let changed: Vec<_> = self
+            .open
+            .range((low + 1, 0, first)..)
+            .take_while(|&&(f, _, _)| f <= high)
+            .copied()
+            .collect();
+        for (f, h, board) in changed {
+            let entry = (self.priority(f, h), f, board);
+            if threshold > self.threshold {
+                self.focal.insert(entry);
+            } else {
+                // only with an inconsistent heuristic
+                self.focal.remove(&entry);
+            }
+        }
+        self.threshold = threshold;
+    }
+}
+
+/// Focal search from `init_state` to `goal`, a bounded-suboptimal variant of A*: the plan found costs
+/// at most `weight` times the optimal cost, as long as the heuristic is admissible.
+///
+/// The open list is ordered by f-value as in A*, but the state expanded is taken from the *focal* list: the states whose
+/// f-value is at most `weight` times the smallest f-value of the open list, which is a lower bound on the optimal cost.
+/// Among them, the state with the smallest `g + weight * h` is expanded, as in weighted A* (ordering them by `h` only,
+/// as in the original A*ε, thrashes on the 15-puzzle). Unlike weighted A*, the bound holds even with
+/// an inconsistent heuristic, and the bound actually achieved (cost of the plan divided by the smallest f-value)
+/// is reported in `Stats::suboptimality_bound`.
+///
+/// Panics if the weight is lower than 1 or larger than `MAX_WEIGHT`.
+pub fn focal_search<const N: usize>(
+    init_state: Board<N>,
+    goal: &Board<N>,
+    heuristic: &Heuristic,
+    weight: f64,
+) -> (SearchOutcome, Stats) {
+    focal_search_with_limits(
+        init_state,
+        goal,
+        heuristic,
+        weight,
+        &SearchLimits::default(),
+    )
+}
+
+/// Focal search (see `focal_search`) that stops as soon as one of the `limits` is reached.
+pub fn focal_search_with_limits<const N: usize>(
+    init_state: Board<N>,
+    goal: &Board<N>,
+    heuristic: &Heuristic,
+    weight: f64,
+    limits: &SearchLimits,
+) -> (SearchOutcome, Stats) {
+    assert!(
+        (1.0..=MAX_WEIGHT).contains(&weight),
+        "Invalid weight {weight}"
+    );
+    let start = std::time::Instant::now();
+    let mut stats = Stats::default();
+    let goal = Goal::new(*goal);
+
+    if !init_state.is_solvable_towards(goal.board()) {
+        stats.runtime = start.elapsed();
+        return (SearchOutcome::Unsolvable, stats);
+    }
+
+    // the cost of the best known path to each state, and the estimate of the state
+    let mut costs: HashMap<Board<N>, (u32, u32)> = HashMap::new();
+    let mut parent_action: HashMap<Board<N>, (Board<N>, Direction)> = HashMap::new();
+    let mut expanded: HashSet<Board<N>> = HashSet::new();
+
+    let init_h = heuristic.estimate_towards(&init_state, &goal);
+    let mut lists = Focal {
+        weight,
+        scaled_weight: (weight * WEIGHT_SCALE as f64).round() as u32,
+        open: BTreeSet::new(),
+        focal: BTreeSet::new(),
+        threshold: (weight * init_h as f64).floor() as u32,
+    };
+    costs.insert(init_state, (0, init_h));
+    lists.insert(init_state, init_h, init_h);
+    stats.record_sizes(lists.open.len(), expanded.len());
+
+    while let Some((_, f, s)) = lists.focal.pop_first() {
+        let (cost, h) = costs[&s];
+        let f_min = lists.open.first().map_or(f, |&(f_min, _, _)| f_min);
+        lists.open.remove(&(f, h, s));
+
+        if s == *goal.board() {
+            let plan = extract_plan(&parent_action, s);
+            let cost = plan.len() as u32;
+            stats.suboptimality_bound = Some(match f_min {
+                0 => 1.0,
+                _ => cost as f64 / f_min as f64,
+            });
+            stats.solution_cost = Some(cost);
+            stats.runtime = start.elapsed();
+            return (SearchOutcome::Solved(plan), stats);
+        }
+        if let Some(outcome) = limits.stop::<N>(&stats, costs.len()) {
+            stats.runtime = start.elapsed();
+            return (outcome, stats);
+        }
+
+        stats.record_expansion(f);
+        let current_cost = cost + 1;
+        let blank = s.position(EMPTY_CELL);
+        for action in DIRECTIONS {
+            let Some(successor) = s.apply(action) else {
+                continue;
+            };
+            stats.generated += 1;
+
+            if let Some(&(previous_cost, previous_h)) = costs.get(&successor) {
+                stats.duplicates += 1;
+                if current_cost >= previous_cost {
+                    continue;
+                }
+                // a cheaper path to a known state: it must be (re)expanded with its new cost
+                if expanded.remove(&successor) {
+                    stats.reopened += 1;
+                } else {
+                    lists.remove(successor, previous_cost + previous_h, previous_h);
+                }
+            }
+            let successor_h =
+                heuristic.estimate_after_move(&s, blank, h, action, &successor, &goal);
+            costs.insert(successor, (current_cost, successor_h));
+            parent_action.insert(successor, (s, action));
+            lists.insert(successor, current_cost + successor_h, successor_h);
+        }
+        expanded.insert(s);
+        lists.update_threshold();
+        stats.record_sizes(lists.open.len(), expanded.len());
+    }
+
+    stats.runtime = start.elapsed();
+    (SearchOutcome::Unsolvable, stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::*;
+    use crate::pattern_database::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_focal_search() {
+        let mut total_expanded = [0; 2];
+        for (cost, board) in &INSTANCES {
+            for (i, weight) in [1.0, 1.5].into_iter().enumerate() {
+                let (outcome, stats) =
+                    focal_search(*board, &Board::GOAL, &Heuristic::Manhattan, weight);
+                let plan = outcome.plan().expect("no plan");
+                assert!(board.is_valid_plan(plan));
+                assert!(plan.len() as f64 <= weight * *cost as f64);
+                let bound = stats.suboptimality_bound.unwrap();
+                assert!((1.0..=weight).contains(&bound));
+                total_expanded[i] += stats.expanded;
+            }
+        }
+        assert!(total_expanded[1] < total_expanded[0]);
+    }
+
+    #[test]
+    fn test_larger_boards() {
+        let board: Board15 = Generator::new(19).random_walk(60);
+        let (outcome, stats) = focal_search(board, &Board::GOAL, &Heuristic::LinearConflict, 2.0);
+        assert!(board.is_valid_plan(outcome.plan().expect("no plan")));
+        assert!(stats.suboptimality_bound.unwrap() <= 2.0);
+
+        let (outcome, stats) =
+            focal_search(board, &Board::spiral_goal(), &Heuristic::Manhattan, 2.0);
+        assert!(outcome.is_solved());
+
+        // the largest weight does not overflow the priorities
+        let (outcome, stats) = focal_search(board, &Board::GOAL, &Heuristic::Manhattan, MAX_WEIGHT);
+        assert!(board.is_valid_plan(outcome.plan().expect("no plan")));
+        assert!(stats.suboptimality_bound.unwrap() <= MAX_WEIGHT);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid weight")]
+    fn test_invalid_weight() {
+        focal_search(Board8::GOAL, &Board::GOAL, &Heuristic::Manhattan, 1e7);
+    }
+
+    #[test]
+    fn test_unsolvable() {
+        let board = Board::new([[2, 1, 3], [4, 5, 6], [7, 8, 0]]);
+        let (outcome, _) = focal_search(board, &Board::GOAL, &Heuristic::Manhattan, 2.0);
+        assert_eq!(outcome, SearchOutcome::Unsolvable);
+    }
+}
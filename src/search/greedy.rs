@@ -0,0 +1,132 @@
+use crate::board::*;
+use crate::goal::*;
+use crate::heuristics::*;
+use crate::min_heap::*;
+use crate::search::*;
+
+/// Greedy best-first search from `init_state` to `goal`: the state expanded is always the one that looks
+/// the closest to the goal, i.e., with the smallest estimate, whatever the cost of the path that reached it.
+///
+/// It usually finds a plan after very few expansions, but the plan may be much longer than the optimal one.
+/// Each state is inserted in the open list at most once: the states already reached are ignored, even through
+/// a cheaper path.
+pub fn greedy_search<const N: usize>(
+    init_state: Board<N>,
+    goal: &Board<N>,
+    heuristic: &Heuristic,
+) -> (SearchOutcome, Stats) {
+    greedy_search_with_limits(init_state, goal, heuristic, &SearchLimits::default())
+}
+
+/// Greedy best-first search (see `greedy_search`) that stops as soon as one of the `limits` is reached.
+pub fn greedy_search_with_limits<const N: usize>(
+    init_state: Board<N>,
+    goal: &Board<N>,
+    heuristic: &Heuristic,
+    limits: &SearchLimits,
+) -> (SearchOutcome, Stats) {
+    let start = std::time::Instant::now();
+    let mut stats = Stats::default();
+    let goal = Goal::new(*goal);
+
+    if !init_state.is_solvable_towards(goal.board()) {
+        stats.runtime = start.elapsed();
+        return (SearchOutcome::Unsolvable, stats);
+    }
+
+    let mut heap: MinHeap<Board<N>> = MinHeap::new();
+    // all the states ever inserted in the open list, with the move that reached them
+    let mut parent_action: HashMap<Board<N>, (Board<N>, Direction)> = HashMap::new();
+    let mut reached: HashSet<Board<N>> = HashSet::new();
+
+    reached.insert(init_state);
+    heap.insert(init_state, heuristic.estimate_towards(&init_state, &goal));
+    stats.record_sizes(heap.len(), reached.len() - heap.len());
+
+    while let Some((s, h)) = heap.pop_with_priority() {
+        if s == *goal.board() {
+            let plan = extract_plan(&parent_action, s);
+            stats.solution_cost = Some(plan.len() as u32);
+            stats.runtime = start.elapsed();
+            return (SearchOutcome::Solved(plan), stats);
+        }
+        if let Some(outcome) = limits.stop::<N>(&stats, reached.len()) {
+            stats.runtime = start.elapsed();
+            return (outcome, stats);
+        }
+
+        // the layers group the expansions by estimate, since the cost of the paths is ignored
+        stats.record_expansion(h);
+        let blank = s.position(EMPTY_CELL);
+        for action in DIRECTIONS {
+            let Some(successor) = s.apply(action) else {
+                continue;
+            };
+            stats.generated += 1;
+            if !reached.insert(successor) {
+                stats.duplicates += 1;
+                continue;
+            }
+            parent_action.insert(successor, (s, action));
+            heap.insert(
+                successor,
+                heuristic.estimate_after_move(&s, blank, h, action, &successor, &goal),
+            );
+        }
+        stats.record_sizes(heap.len(), reached.len() - heap.len());
+    }
+
+    stats.runtime = start.elapsed();
+    (SearchOutcome::Unsolvable, stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::*;
+
+    #[test]
+    fn test_greedy_search() {
+        // greedy plans are valid but longer, and found after far fewer expansions than A*
+        let (mut greedy_length, mut astar_length) = (0, 0);
+        let (mut greedy_expanded, mut astar_expanded) = (0, 0);
+        for (cost, board) in &INSTANCES {
+            let (outcome, stats) = greedy_search(*board, &Board::GOAL, &Heuristic::LinearConflict);
+            let plan = outcome.plan().expect("no plan");
+            assert!(board.is_valid_plan(plan));
+            assert!(plan.len() >= *cost as usize);
+            assert_eq!(stats.solution_cost, Some(plan.len() as u32));
+            greedy_length += plan.len();
+            greedy_expanded += stats.expanded;
+
+            let (_, stats) = search(*board, &Board::GOAL, &Heuristic::LinearConflict);
+            astar_length += *cost as usize;
+            astar_expanded += stats.expanded;
+        }
+        assert!(greedy_length > astar_length);
+        assert!(greedy_expanded < astar_expanded);
+    }
+
+    #[test]
+    fn test_larger_boards() {
+        // a random 24-puzzle, far out of reach of the optimal algorithms
+        let board: Board24 = Generator::new(5).random_solvable();
+        let (outcome, stats) = greedy_search(board, &Board::GOAL, &Heuristic::LinearConflict);
+        assert!(board.is_valid_plan(outcome.plan().expect("no plan")));
+        assert!(stats.layers.iter().all(|layer| layer.expanded > 0));
+
+        let goal = Board::spiral_goal();
+        let board: Board15 = Generator::new(6).random_solvable();
+        let (outcome, _) = greedy_search(board, &goal, &Heuristic::Manhattan);
+        assert!(board
+            .validate_plan_towards(outcome.plan().expect("no plan"), &goal)
+            .is_valid());
+    }
+
+    #[test]
+    fn test_unsolvable() {
+        let board = Board::new([[2, 1, 3], [4, 5, 6], [7, 8, 0]]);
+        let (outcome, _) = greedy_search(board, &Board::GOAL, &Heuristic::Manhattan);
+        assert_eq!(outcome, SearchOutcome::Unsolvable);
+    }
+}
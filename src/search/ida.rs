@@ -0,0 +1,196 @@
+use crate::board::*;
+use crate::goal::*;
+use crate::heuristics::*;
+use crate::search::*;
+
+/// Result of a bounded depth-first exploration.
+enum Bounded {
+    /// The goal was reached, the plan is in the path.
+    Found,
+    /// The goal was not reached within the bound.
+    /// Contains the smallest f-value that exceeded the bound, if any.
+    Exceeded(Option<u32>),
+    /// The search was stopped by one of its limits or cancelled, with this outcome.
+    Stopped(SearchOutcome),
+}
+
+/// State of an IDA* search shared by all recursive calls.
+struct Ida<'a, const N: usize> {
+    heuristic: &'a Heuristic,
+    goal: Goal<N>,
+    /// Moves leading from the initial state to the current one.
+    path: Vec<Direction>,
+    limits: &'a SearchLimits,
+    stats: Stats,
+}
+
+impl<const N: usize> Ida<'_, N> {
+    /// Depth-first exploration of the states reachable from `board` (reached with cost `g`, estimated at `h`, and whose
+    /// empty cell is at `blank`) whose f-value does not exceed the `bound`.
+    fn explore(
+        &mut self,
+        board: &Board<N>,
+        blank: (usize, usize),
+        g: u32,
+        h: u32,
+        bound: u32,
+    ) -> Bounded {
+        let f = g + h;
+        if f > bound {
+            return Bounded::Exceeded(Some(f));
+        }
+        if board == self.goal.board() {
+            return Bounded::Found;
+        }
+        if let Some(outcome) = self.limits.stop::<N>(&self.stats, self.path.len() + 1) {
+            return Bounded::Stopped(outcome);
+        }
+
+        self.stats.record_expansion(bound);
+        self.stats.record_sizes(self.path.len() + 1, 0);
+
+        let mut next_bound: Option<u32> = None;
+        for action in DIRECTIONS {
+            // never undo the previous move: it would lead back to the parent state
+            if self.path.last() == Some(&action.opposite()) {
+                continue;
+            }
+            let Some(successor) = board.apply(action) else {
+                continue;
+            };
+            self.stats.generated += 1;
+
+            let successor_h = self
+                .heuristic
+                .estimate_after_move(board, blank, h, action, &successor, &self.goal);
+            self.path.push(action);
+            match self.explore(&successor, action.step(blank), g + 1, successor_h, bound) {
+                Bounded::Found => return Bounded::Found,
+                stopped @ Bounded::Stopped(_) => return stopped,
+                Bounded::Exceeded(Some(successor_f)) => {
+                    next_bound = Some(next_bound.map_or(successor_f, |b| b.min(successor_f)))
+                }
+                Bounded::Exceeded(None) => {}
+            }
+            self.path.pop();
+        }
+        Bounded::Exceeded(next_bound)
+    }
+}
+
+/// Iterative deepening A* (IDA*) from `init_state` to `goal`, guided by the given heuristic.
+///
+/// Each iteration is a depth-first search that prunes the states whose f-value exceeds a threshold.
+/// The first threshold is the heuristic value of the initial state, and each following one is the smallest f-value
+/// that exceeded the previous threshold. Only the current path is kept in memory, which makes it suitable for
+/// the 15-puzzle where the open and closed lists of A* would not fit in memory.
+///
+/// Each iteration is reported as one of the `Stats::layers`, with the threshold as its f-value.
+/// `Stats::peak_open` is the maximum length of the path explored and no closed list is maintained.
+pub fn ida_star<const N: usize>(
+    init_state: Board<N>,
+    goal: &Board<N>,
+    heuristic: &Heuristic,
+) -> (SearchOutcome, Stats) {
+    ida_star_with_limits(init_state, goal, heuristic, &SearchLimits::default())
+}
+
+/// IDA* (see `ida_star`) that stops as soon as one of the `limits` is reached.
+pub fn ida_star_with_limits<const N: usize>(
+    init_state: Board<N>,
+    goal: &Board<N>,
+    heuristic: &Heuristic,
+    limits: &SearchLimits,
+) -> (SearchOutcome, Stats) {
+    let start = std::time::Instant::now();
+    let mut ida = Ida {
+        heuristic,
+        goal: Goal::new(*goal),
+        path: Vec::new(),
+        limits,
+        stats: Stats::default(),
+    };
+
+    if !init_state.is_solvable_towards(goal) {
+        ida.stats.runtime = start.elapsed();
+        return (SearchOutcome::Unsolvable, ida.stats);
+    }
+
+    let init_h = heuristic.estimate_towards(&init_state, &ida.goal);
+    let init_blank = init_state.position(EMPTY_CELL);
+    let mut bound = init_h;
+    let outcome = loop {
+        match ida.explore(&init_state, init_blank, 0, init_h, bound) {
+            Bounded::Found => {
+                ida.stats.solution_cost = Some(ida.path.len() as u32);
+                break SearchOutcome::Solved(std::mem::take(&mut ida.path));
+            }
+            Bounded::Exceeded(Some(next_bound)) => bound = next_bound,
+            // nothing was pruned: the whole connected component was explored
+            Bounded::Exceeded(None) => break SearchOutcome::Unsolvable,
+            Bounded::Stopped(outcome) => break outcome,
+        }
+    };
+    ida.stats.runtime = start.elapsed();
+    (outcome, ida.stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ida_star() {
+        for (expected_cost, init) in &INSTANCES {
+            let (outcome, stats) = ida_star(*init, &Board::GOAL, &Heuristic::Manhattan);
+            let path = outcome.plan().expect("no plan");
+            assert!(init.is_valid_plan(path));
+            assert_eq!(path.len(), *expected_cost as usize);
+            assert_eq!(stats.solution_cost, Some(*expected_cost));
+            assert_eq!(stats.peak_closed, 0);
+            // one layer per iteration, with increasing thresholds ending at the optimal cost
+            assert!(stats.layers.windows(2).all(|w| w[0].f < w[1].f));
+            if *expected_cost > 0 {
+                assert_eq!(stats.layers.last().unwrap().f, *expected_cost);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ida_star_larger_boards() {
+        use Direction::*;
+
+        let init = [
+            Up, Up, Left, Down, Left, Up, Up, Right, Down, Left, Left, Down, Right, Up, Left, Down,
+            Down, Right, Right, Up, Left, Left, Up, Up,
+        ]
+        .iter()
+        .fold(Board15::GOAL, |board, &d| board.apply(d).unwrap());
+        let (outcome, stats) = ida_star(init, &Board::GOAL, &Heuristic::Manhattan);
+        let path = outcome.plan().expect("no plan");
+        assert!(init.is_valid_plan(path));
+        let (astar_outcome, _) = search(init, &Board::GOAL, &Heuristic::Manhattan);
+        assert_eq!(path.len(), astar_outcome.plan().unwrap().len());
+        assert_eq!(stats.solution_cost, Some(path.len() as u32));
+    }
+
+    #[test]
+    fn test_ida_star_other_goal() {
+        let goal = Board::blank_first_goal();
+        for (_, init) in &INSTANCES[0..16] {
+            let (outcome, _) = ida_star(*init, &goal, &Heuristic::LinearConflict);
+            let plan = outcome.plan().expect("no plan");
+            assert!(init.validate_plan_towards(plan, &goal).is_valid());
+            let (optimal, _) = search(*init, &goal, &Heuristic::Manhattan);
+            assert_eq!(plan.len(), optimal.plan().unwrap().len());
+        }
+    }
+
+    #[test]
+    fn test_ida_star_unsolvable() {
+        let init = Board::new([[2, 1, 3], [4, 5, 6], [7, 8, 0]]);
+        let (outcome, stats) = ida_star(init, &Board::GOAL, &Heuristic::Manhattan);
+        assert_eq!(outcome, SearchOutcome::Unsolvable);
+        assert_eq!(stats.expanded, 0);
+    }
+}
@@ -0,0 +1,222 @@
+use crate::board::*;
+use crate::min_heap::*;
+use crate::search::*;
+
+/// Breadth-first search from `init_state` to `goal`: the states are expanded in the order in which they were reached
+/// (first in, first out), so that the plan found is optimal since all moves have the same cost.
+///
+/// The goal test is done when a state is generated rather than when it is expanded, which saves the expansion
+/// of the whole last layer. Each layer of `Stats::layers` is a depth of the search.
+pub fn breadth_first_search<const N: usize>(
+    init_state: Board<N>,
+    goal: &Board<N>,
+) -> (SearchOutcome, Stats) {
+    breadth_first_search_with_limits(init_state, goal, &SearchLimits::default())
+}
+
+/// Breadth-first search (see `breadth_first_search`) that stops as soon as one of the `limits` is reached.
+pub fn breadth_first_search_with_limits<const N: usize>(
+    init_state: Board<N>,
+    goal: &Board<N>,
+    limits: &SearchLimits,
+) -> (SearchOutcome, Stats) {
+    let start = std::time::Instant::now();
+    let mut stats = Stats::default();
+
+    if !init_state.is_solvable_towards(goal) {
+        stats.runtime = start.elapsed();
+        return (SearchOutcome::Unsolvable, stats);
+    }
+    if init_state == *goal {
+        stats.solution_cost = Some(0);
+        stats.runtime = start.elapsed();
+        return (SearchOutcome::Solved(Vec::new()), stats);
+    }
+
+    // the states to expand, with their depth
+    let mut queue: VecDeque<(Board<N>, u32)> = VecDeque::new();
+    // the move that first reached each state, the initial state being the only reached state without a parent
+    let mut parent_action: HashMap<Board<N>, (Board<N>, Direction)> = HashMap::new();
+    let mut reached: HashSet<Board<N>> = HashSet::new();
+
+    queue.push_back((init_state, 0));
+    reached.insert(init_state);
+    stats.record_sizes(queue.len(), 0);
+
+    while let Some((s, depth)) = queue.pop_front() {
+        if let Some(outcome) = limits.stop::<N>(&stats, reached.len()) {
+            stats.runtime = start.elapsed();
+            return (outcome, stats);
+        }
+        stats.record_expansion(depth);
+        for action in DIRECTIONS {
+            let Some(successor) = s.apply(action) else {
+                continue;
+            };
+            stats.generated += 1;
+            if !reached.insert(successor) {
+                stats.duplicates += 1;
+                continue;
+            }
+            parent_action.insert(successor, (s, action));
+            if successor == *goal {
+                let plan = extract_plan(&parent_action, successor);
+                stats.solution_cost = Some(plan.len() as u32);
+                stats.runtime = start.elapsed();
+                return (SearchOutcome::Solved(plan), stats);
+            }
+            queue.push_back((successor, depth + 1));
+        }
+        stats.record_sizes(queue.len(), reached.len() - queue.len());
+    }
+
+    stats.runtime = start.elapsed();
+    (SearchOutcome::Unsolvable, stats)
+}
+
+/// Dijkstra's algorithm (uniform-cost search) from `init_state` to `goal`: the states are expanded by increasing cost
+/// of the best known path, i.e., A* without heuristic, but without any of its machinery.
+///
+/// Each layer of `Stats::layers` groups the states expanded with the same cost.
+pub fn dijkstra<const N: usize>(init_state: Board<N>, goal: &Board<N>) -> (SearchOutcome, Stats) {
+    dijkstra_with_limits(init_state, goal, &SearchLimits::default())
+}
+
+/// Dijkstra's algorithm (see `dijkstra`) that stops as soon as one of the `limits` is reached.
+pub fn dijkstra_with_limits<const N: usize>(
+    init_state: Board<N>,
+    goal: &Board<N>,
+    limits: &SearchLimits,
+) -> (SearchOutcome, Stats) {
+    let start = std::time::Instant::now();
+    let mut stats = Stats::default();
+
+    if !init_state.is_solvable_towards(goal) {
+        stats.runtime = start.elapsed();
+        return (SearchOutcome::Unsolvable, stats);
+    }
+
+    let mut heap: MinHeap<Board<N>> = MinHeap::new();
+    let mut costs: HashMap<Board<N>, u32> = HashMap::new();
+    let mut parent_action: HashMap<Board<N>, (Board<N>, Direction)> = HashMap::new();
+    let mut expanded: HashSet<Board<N>> = HashSet::new();
+
+    costs.insert(init_state, 0);
+    heap.insert(init_state, 0);
+    stats.record_sizes(heap.len(), 0);
+
+    while let Some((s, cost)) = heap.pop_with_priority() {
+        if !expanded.insert(s) {
+            // already expanded through a path at least as cheap
+            continue;
+        }
+        if s == *goal {
+            let plan = extract_plan(&parent_action, s);
+            stats.solution_cost = Some(cost);
+            stats.runtime = start.elapsed();
+            return (SearchOutcome::Solved(plan), stats);
+        }
+        if let Some(outcome) = limits.stop::<N>(&stats, costs.len()) {
+            stats.runtime = start.elapsed();
+            return (outcome, stats);
+        }
+
+        stats.record_expansion(cost);
+        for action in DIRECTIONS {
+            let Some(successor) = s.apply(action) else {
+                continue;
+            };
+            stats.generated += 1;
+            match costs.get(&successor) {
+                Some(&previous_cost) if previous_cost <= cost + 1 => stats.duplicates += 1,
+                _ => {
+                    costs.insert(successor, cost + 1);
+                    parent_action.insert(successor, (s, action));
+                    heap.insert(successor, cost + 1);
+                }
+            }
+        }
+        stats.record_sizes(heap.len(), expanded.len());
+    }
+
+    stats.runtime = start.elapsed();
+    (SearchOutcome::Unsolvable, stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::*;
+    use crate::heuristics::*;
+
+    #[test]
+    fn test_breadth_first_search() {
+        for (cost, board) in &INSTANCES[..20] {
+            let (outcome, stats) = breadth_first_search(*board, &Board::GOAL);
+            let plan = outcome.plan().expect("no plan");
+            assert!(board.is_valid_plan(plan));
+            assert_eq!(plan.len(), *cost as usize);
+            assert_eq!(stats.solution_cost, Some(*cost));
+            // one layer per depth, the goal being found while expanding the last one
+            assert_eq!(stats.layers.len(), *cost as usize);
+            assert!(stats
+                .layers
+                .iter()
+                .enumerate()
+                .all(|(depth, layer)| layer.f == depth as u32));
+        }
+        let (outcome, stats) = breadth_first_search(Board8::GOAL, &Board::GOAL);
+        assert_eq!(outcome, SearchOutcome::Solved(vec![]));
+        assert_eq!(stats.expanded, 0);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        for (cost, board) in &INSTANCES[..16] {
+            let (outcome, stats) = dijkstra(*board, &Board::GOAL);
+            let plan = outcome.plan().expect("no plan");
+            assert!(board.is_valid_plan(plan));
+            assert_eq!(plan.len(), *cost as usize);
+            assert_eq!(stats.reopened, 0);
+            // the goal test on generation saves the expansion of (most of) the last layer
+            let (_, bfs) = breadth_first_search(*board, &Board::GOAL);
+            assert!(bfs.expanded <= stats.expanded);
+        }
+    }
+
+    #[test]
+    fn test_reference() {
+        // the plans of the informed algorithms are as short as the breadth-first ones
+        let mut generator = Generator::new(17);
+        for _ in 0..10 {
+            let board: Board8 = generator.random_walk(12);
+            let (reference, _) = breadth_first_search(board, &Board::GOAL);
+            let cost = reference.plan().unwrap().len();
+            for (outcome, _) in [
+                search(board, &Board::GOAL, &Heuristic::LinearConflict),
+                ida_star(board, &Board::GOAL, &Heuristic::LinearConflict),
+                bidirectional_search(board, &Board::GOAL, &Heuristic::Manhattan),
+                dijkstra(board, &Board::GOAL),
+            ] {
+                assert_eq!(outcome.plan().map(|p| p.len()), Some(cost));
+            }
+        }
+        let goal = Board15::spiral_goal();
+        let board = goal
+            .apply(Direction::Up)
+            .unwrap()
+            .apply(Direction::Left)
+            .unwrap();
+        let (reference, _) = breadth_first_search(board, &goal);
+        assert_eq!(
+            reference,
+            SearchOutcome::Solved(vec![Direction::Right, Direction::Down])
+        );
+        let board = Board::new([[2, 1, 3], [4, 5, 6], [7, 8, 0]]);
+        assert_eq!(
+            breadth_first_search(board, &Board::GOAL).0,
+            SearchOutcome::Unsolvable
+        );
+        assert_eq!(dijkstra(board, &Board::GOAL).0, SearchOutcome::Unsolvable);
+    }
+}